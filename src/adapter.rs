@@ -1,8 +1,50 @@
+mod bun_test;
+mod cargo_nextest;
+mod cargo_test;
+mod cpp_catch2;
+mod cpp_gtest;
+mod dart_test;
+mod deno_test;
+mod dotnet_test;
+mod elixir_ex_unit;
+mod go_test;
+mod haskell_test;
+mod js_jest;
+mod js_mocha;
+mod js_vitest;
+mod jvm_junit;
+mod lua_busted;
+mod php_unit;
+mod python_pytest;
+mod python_unittest;
+mod ruby_cucumber;
 mod ruby_minitest;
 mod ruby_rspec;
+mod shell_bats;
 
+pub use self::bun_test::BunTest;
+pub use self::cargo_nextest::CargoNextest;
+pub use self::cargo_test::CargoTest;
+pub use self::cpp_catch2::CppCatch2;
+pub use self::cpp_gtest::CppGTest;
+pub use self::dart_test::DartTest;
+pub use self::deno_test::DenoTest;
+pub use self::dotnet_test::DotnetTest;
+pub use self::elixir_ex_unit::ElixirExUnit;
+pub use self::go_test::GoTest;
+pub use self::haskell_test::HaskellTest;
+pub use self::js_jest::JsJest;
+pub use self::js_mocha::JsMocha;
+pub use self::js_vitest::JsVitest;
+pub use self::jvm_junit::JvmJunit;
+pub use self::lua_busted::LuaBusted;
+pub use self::php_unit::PhpUnit;
+pub use self::python_pytest::PythonPytest;
+pub use self::python_unittest::PythonUnittest;
+pub use self::ruby_cucumber::RubyCucumber;
 pub use self::ruby_minitest::RubyMinitest;
 pub use self::ruby_rspec::RubyRspec;
+pub use self::shell_bats::ShellBats;
 
 use crate::selector_match::SelectorMatch;
 use crate::{define_adapters, TestSelector};
@@ -10,8 +52,29 @@ use enum_dispatch::enum_dispatch;
 use std::borrow::Cow;
 
 define_adapters! {
-    "ruby_rspec" => RubyRspec,
-    "ruby_minitest" => RubyMinitest
+    ruby_cucumber => RubyCucumber,
+    ruby_rspec => RubyRspec,
+    ruby_minitest => RubyMinitest,
+    cargo_nextest => CargoNextest,
+    cargo_test => CargoTest,
+    python_pytest => PythonPytest,
+    python_unittest => PythonUnittest,
+    go_test => GoTest,
+    cpp_gtest => CppGTest,
+    cpp_catch2 => CppCatch2,
+    deno_test => DenoTest,
+    bun_test => BunTest,
+    js_vitest => JsVitest,
+    js_mocha => JsMocha,
+    js_jest => JsJest,
+    jvm_junit => JvmJunit,
+    php_unit => PhpUnit,
+    elixir_ex_unit => ElixirExUnit,
+    dotnet_test => DotnetTest,
+    shell_bats => ShellBats,
+    lua_busted => LuaBusted,
+    haskell_test => HaskellTest,
+    dart_test => DartTest
 }
 
 /// The interface a test adapter must implement to be used by the test runner.
@@ -27,19 +90,120 @@ pub trait TestAdapter {
 mod tests {
     use std::convert::TryInto;
 
-    use crate::adapter::{Adapter, RubyMinitest, RubyRspec};
+    use crate::adapter::{
+        Adapter, BunTest, CargoNextest, CargoTest, CppCatch2, CppGTest, DartTest, DenoTest,
+        DotnetTest, ElixirExUnit, GoTest, HaskellTest, JsJest, JsMocha, JsVitest, JvmJunit,
+        LuaBusted, PhpUnit, PythonPytest, PythonUnittest, RubyCucumber, RubyMinitest, RubyRspec,
+        ShellBats,
+    };
+    use crate::config::Config;
 
     #[test]
     fn from_str_works() {
         assert_eq!(
-            Adapter::RubyRspec(RubyRspec {}),
+            Adapter::RubyRspec(RubyRspec::default()),
             "ruby_rspec".try_into().unwrap(),
         );
         assert_eq!(
-            Adapter::RubyMinitest(RubyMinitest {}),
+            Adapter::RubyMinitest(RubyMinitest::default()),
             "ruby_minitest".try_into().unwrap(),
         );
+        assert_eq!(
+            Adapter::CargoNextest(CargoNextest::default()),
+            "cargo_nextest".try_into().unwrap(),
+        );
+        assert_eq!(
+            Adapter::CargoTest(CargoTest::default()),
+            "cargo_test".try_into().unwrap(),
+        );
+        assert_eq!(
+            Adapter::PythonPytest(PythonPytest::default()),
+            "python_pytest".try_into().unwrap(),
+        );
+        assert_eq!(
+            Adapter::PythonUnittest(PythonUnittest::default()),
+            "python_unittest".try_into().unwrap(),
+        );
+        assert_eq!(
+            Adapter::GoTest(GoTest::default()),
+            "go_test".try_into().unwrap(),
+        );
+        assert_eq!(
+            Adapter::JsJest(JsJest::default()),
+            "js_jest".try_into().unwrap(),
+        );
+        assert_eq!(
+            Adapter::JsVitest(JsVitest::default()),
+            "js_vitest".try_into().unwrap(),
+        );
+        assert_eq!(
+            Adapter::JsMocha(JsMocha::default()),
+            "js_mocha".try_into().unwrap(),
+        );
+        assert_eq!(
+            Adapter::JvmJunit(JvmJunit::default()),
+            "jvm_junit".try_into().unwrap(),
+        );
+        assert_eq!(
+            Adapter::PhpUnit(PhpUnit::default()),
+            "php_unit".try_into().unwrap(),
+        );
+        assert_eq!(
+            Adapter::ElixirExUnit(ElixirExUnit::default()),
+            "elixir_ex_unit".try_into().unwrap(),
+        );
+        assert_eq!(
+            Adapter::RubyCucumber(RubyCucumber::default()),
+            "ruby_cucumber".try_into().unwrap(),
+        );
+        assert_eq!(
+            Adapter::DotnetTest(DotnetTest::default()),
+            "dotnet_test".try_into().unwrap(),
+        );
+        assert_eq!(
+            Adapter::ShellBats(ShellBats::default()),
+            "shell_bats".try_into().unwrap(),
+        );
+        assert_eq!(
+            Adapter::LuaBusted(LuaBusted::default()),
+            "lua_busted".try_into().unwrap(),
+        );
+        assert_eq!(
+            Adapter::DenoTest(DenoTest::default()),
+            "deno_test".try_into().unwrap(),
+        );
+        assert_eq!(
+            Adapter::BunTest(BunTest::default()),
+            "bun_test".try_into().unwrap(),
+        );
+        assert_eq!(
+            Adapter::CppGTest(CppGTest::default()),
+            "cpp_gtest".try_into().unwrap(),
+        );
+        assert_eq!(
+            Adapter::CppCatch2(CppCatch2::default()),
+            "cpp_catch2".try_into().unwrap(),
+        );
+        assert_eq!(
+            Adapter::HaskellTest(HaskellTest::default()),
+            "haskell_test".try_into().unwrap(),
+        );
+        assert_eq!(
+            Adapter::DartTest(DartTest::default()),
+            "dart_test".try_into().unwrap(),
+        );
 
         assert!(Adapter::try_from("does_not_exist").is_err());
     }
+
+    #[test]
+    fn from_config_uses_the_adapter_config() {
+        let config = Config::from_toml("[ruby-rspec]\nfile-patterns = ['_check\\.rb$']").unwrap();
+        let config = Config::default().merge(&config);
+
+        assert_eq!(
+            Adapter::RubyRspec(RubyRspec::new(config.ruby_rspec.clone())),
+            Adapter::from_config("ruby_rspec", &config).unwrap(),
+        );
+    }
 }
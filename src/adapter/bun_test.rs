@@ -0,0 +1,134 @@
+use super::{js_jest::find_name_pattern, SelectorMatch, TestAdapter};
+use crate::{
+    config::{AdapterConfig, Config},
+    none_if_empty,
+    selector_match::{exclusive_or_none, shared_or_none},
+    utils::file_exists,
+    TestSelector,
+};
+use std::borrow::Cow;
+use TestSelector::*;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct BunTest {
+    config: AdapterConfig,
+}
+
+impl BunTest {
+    /// Creates a new BunTest adapter.
+    pub const fn new(config: AdapterConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Default for BunTest {
+    fn default() -> Self {
+        Self::new(Config::default().bun_test.clone())
+    }
+}
+
+impl TestAdapter for BunTest {
+    fn selector_matches(&self, selector: &TestSelector) -> SelectorMatch {
+        match selector {
+            PathWithLineNumber { path, .. } | PathOnly { path } => {
+                exclusive_or_none(uses_bun() && path.matches(&self.config.file_patterns))
+            }
+            NameOnly { .. } => shared_or_none(uses_bun()),
+        }
+    }
+
+    fn collect_commands(&self, selectors: &[&TestSelector]) -> Option<Vec<Cow<'_, str>>> {
+        let mut commands: Vec<Cow<str>> = vec![];
+
+        for selector in selectors {
+            let mut args = vec!["bun".to_string(), "test".to_string()];
+
+            match selector {
+                PathWithLineNumber { path, line } => {
+                    args.push(path.to_string_lossy().to_string());
+
+                    if let Some(pattern) = find_name_pattern(&self.config, path, *line) {
+                        args.extend(["-t".to_string(), pattern]);
+                    }
+                }
+                PathOnly { path } => args.push(path.to_string_lossy().to_string()),
+                NameOnly { name } => args.extend(["-t".to_string(), name.to_string()]),
+            };
+
+            commands.push(shell_words::join(args).into());
+        }
+
+        none_if_empty!(commands)
+    }
+}
+
+/// Returns true if the project installs its packages with Bun, and so runs its
+/// tests with it too.
+pub(super) fn uses_bun() -> bool {
+    file_exists("bun.lockb") || file_exists("bun.lock")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_env;
+
+    const TEST_CONTENT: &str = r#"import { describe, expect, test } from "bun:test";
+
+describe("math", () => {
+  test("adds numbers", () => {
+    expect(1 + 1).toBe(2);
+  });
+});
+"#;
+
+    #[test]
+    fn selector_matches_with_bun_lockfile() {
+        for lockfile in ["bun.lockb", "bun.lock"] {
+            test_env::with(|env| {
+                env.write_file("src/math.test.ts", TEST_CONTENT);
+                let adapter = BunTest::default();
+                let selector = env.selector("src/math.test.ts:5");
+
+                assert_eq!(SelectorMatch::None, adapter.selector_matches(&selector));
+
+                env.write_file(lockfile, "");
+
+                assert_eq!(
+                    SelectorMatch::Exclusive,
+                    adapter.selector_matches(&selector)
+                );
+                assert_eq!(
+                    SelectorMatch::Shared,
+                    adapter.selector_matches(&env.selector("adds numbers"))
+                );
+            })
+        }
+    }
+
+    #[test]
+    fn collect_commands_path_with_line_number() {
+        test_env::with(|env| {
+            env.write_file("src/math.test.ts", TEST_CONTENT);
+
+            assert_eq!(
+                env.commands(&BunTest::default(), &["src/math.test.ts:5"]),
+                vec!["bun test src/math.test.ts -t '^math adds numbers$'"]
+            );
+            assert_eq!(
+                env.commands(&BunTest::default(), &["src/math.test.ts:1"]),
+                vec!["bun test src/math.test.ts"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_name_only() {
+        test_env::with(|env| {
+            assert_eq!(
+                env.commands(&BunTest::default(), &["adds numbers"]),
+                vec!["bun test -t 'adds numbers'"]
+            );
+        })
+    }
+}
@@ -0,0 +1,206 @@
+use super::{
+    cargo_test::{find_line_filter, Filter, RustSource},
+    SelectorMatch, TestAdapter,
+};
+use crate::{
+    config::{AdapterConfig, Config},
+    none_if_empty,
+    selector_match::{exclusive_or_none, shared_or_none},
+    utils::file_exists,
+    TestSelector,
+};
+use std::borrow::Cow;
+use TestSelector::*;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CargoNextest {
+    config: AdapterConfig,
+}
+
+impl CargoNextest {
+    /// Creates a new CargoNextest adapter.
+    pub const fn new(config: AdapterConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Default for CargoNextest {
+    fn default() -> Self {
+        Self::new(Config::default().cargo_nextest.clone())
+    }
+}
+
+impl TestAdapter for CargoNextest {
+    fn selector_matches(&self, selector: &TestSelector) -> SelectorMatch {
+        match selector {
+            PathWithLineNumber { path, .. } | PathOnly { path } => {
+                exclusive_or_none(uses_nextest() && path.matches(&self.config.file_patterns))
+            }
+            NameOnly { .. } => shared_or_none(uses_nextest() && file_exists("Cargo.toml")),
+        }
+    }
+
+    fn collect_commands(&self, selectors: &[&TestSelector]) -> Option<Vec<Cow<'_, str>>> {
+        let mut commands: Vec<Cow<str>> = vec![];
+
+        for selector in selectors {
+            match selector {
+                PathWithLineNumber { path, line } => {
+                    let source = RustSource::locate(path);
+                    let filter = find_line_filter(&self.config, &source, path, *line);
+
+                    commands.push(nextest_command(&source, filter.as_ref()).into());
+                }
+                PathOnly { path } => {
+                    let source = RustSource::locate(path);
+                    let filter = source.module_filter();
+
+                    commands.push(nextest_command(&source, filter.as_ref()).into());
+                }
+                NameOnly { name } => {
+                    let filter = Filter::Contains(name.to_string());
+
+                    commands.push(nextest_command(&RustSource::default(), Some(&filter)).into());
+                }
+            };
+        }
+
+        none_if_empty!(commands)
+    }
+}
+
+/// Returns true if the project is configured to run its tests with nextest.
+pub(super) fn uses_nextest() -> bool {
+    file_exists(".config/nextest.toml")
+}
+
+/// Builds the `cargo nextest run` command, with a filter expression selecting
+/// the package, binary and tests.
+fn nextest_command(source: &RustSource, filter: Option<&Filter>) -> String {
+    let mut expressions = vec![];
+
+    if let Some(package) = &source.package {
+        expressions.push(format!("package({package})"));
+    }
+    if let Some((_, binary)) = &source.target {
+        expressions.push(format!("binary({binary})"));
+    }
+    match filter {
+        Some(Filter::Exact(path)) => expressions.push(format!("test(={path})")),
+        Some(Filter::Module(path)) => {
+            expressions.push(format!("test(/^{}::/)", regex::escape(path)))
+        }
+        Some(Filter::Contains(name)) => expressions.push(format!("test({name})")),
+        None => {}
+    }
+
+    let mut args = vec![
+        "cargo".to_string(),
+        "nextest".to_string(),
+        "run".to_string(),
+    ];
+
+    if !expressions.is_empty() {
+        args.extend(["-E".to_string(), expressions.join(" & ")]);
+    }
+
+    shell_words::join(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_env;
+
+    const TEST_CONTENT: &str = r#"#[cfg(test)]
+mod tests {
+    #[test]
+    fn adds() {
+        assert_eq!(1 + 2, 3);
+    }
+}
+"#;
+
+    fn write_package(env: &test_env::TestEnv) {
+        env.write_file("Cargo.toml", "[package]\nname = \"my-crate\"\n");
+        env.write_file(".config/nextest.toml", "");
+        env.write_file("src/lib.rs", "mod ops;\n");
+        env.write_file("src/ops.rs", TEST_CONTENT);
+    }
+
+    #[test]
+    fn selector_matches_only_with_nextest_config() {
+        test_env::with(|env| {
+            env.write_file("Cargo.toml", "[package]\nname = \"my-crate\"\n");
+            env.write_file("src/ops.rs", TEST_CONTENT);
+            let adapter = CargoNextest::default();
+            let selector = env.selector("src/ops.rs:4");
+
+            assert_eq!(SelectorMatch::None, adapter.selector_matches(&selector));
+
+            env.write_file(".config/nextest.toml", "");
+
+            assert_eq!(
+                SelectorMatch::Exclusive,
+                adapter.selector_matches(&selector)
+            );
+            assert_eq!(
+                SelectorMatch::Shared,
+                adapter.selector_matches(&env.selector("adds"))
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_path_with_line_number() {
+        test_env::with(|env| {
+            write_package(env);
+
+            assert_eq!(
+                env.commands(&CargoNextest::default(), &["src/ops.rs:5"]),
+                vec!["cargo nextest run -E 'package(my-crate) & test(=ops::tests::adds)'"]
+            );
+            assert_eq!(
+                env.commands(&CargoNextest::default(), &["src/ops.rs:2"]),
+                vec![r"cargo nextest run -E 'package(my-crate) & test(/^ops::tests::/)'"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_path_only() {
+        test_env::with(|env| {
+            write_package(env);
+
+            assert_eq!(
+                env.commands(&CargoNextest::default(), &["src/ops.rs"]),
+                vec!["cargo nextest run -E 'package(my-crate) & test(/^ops::/)'"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_name_only() {
+        test_env::with(|env| {
+            write_package(env);
+
+            assert_eq!(
+                env.commands(&CargoNextest::default(), &["adds"]),
+                vec!["cargo nextest run -E 'test(adds)'"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_for_integration_tests() {
+        test_env::with(|env| {
+            write_package(env);
+            env.write_file("tests/it.rs", "#[test]\nfn works() {}\n");
+
+            assert_eq!(
+                env.commands(&CargoNextest::default(), &["tests/it.rs:2"]),
+                vec!["cargo nextest run -E 'package(my-crate) & binary(it) & test(=works)'"]
+            );
+        })
+    }
+}
@@ -0,0 +1,420 @@
+use super::{cargo_nextest::uses_nextest, SelectorMatch, TestAdapter};
+use crate::{
+    config::{AdapterConfig, Config},
+    none_if_empty,
+    selector_match::{exclusive_or_none, shared_or_none},
+    test_file::TestFile,
+    test_index::TestIndex,
+    utils::{file_exists, find_up},
+    TestSelector,
+};
+use std::{
+    borrow::Cow,
+    env::current_dir,
+    path::{Component, Path},
+};
+use TestSelector::*;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CargoTest {
+    config: AdapterConfig,
+}
+
+impl CargoTest {
+    /// Creates a new CargoTest adapter.
+    pub const fn new(config: AdapterConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Default for CargoTest {
+    fn default() -> Self {
+        Self::new(Config::default().cargo_test.clone())
+    }
+}
+
+impl TestAdapter for CargoTest {
+    fn selector_matches(&self, selector: &TestSelector) -> SelectorMatch {
+        match selector {
+            PathWithLineNumber { path, .. } | PathOnly { path } => {
+                exclusive_or_none(path.matches(&self.config.file_patterns))
+            }
+            NameOnly { .. } => shared_or_none(file_exists("Cargo.toml") && !uses_nextest()),
+        }
+    }
+
+    fn collect_commands(&self, selectors: &[&TestSelector]) -> Option<Vec<Cow<'_, str>>> {
+        let mut commands: Vec<Cow<str>> = vec![];
+
+        for selector in selectors {
+            match selector {
+                PathWithLineNumber { path, line } => {
+                    let source = RustSource::locate(path);
+                    let filter = find_line_filter(&self.config, &source, path, *line);
+
+                    commands.push(source.command(filter.as_ref()).into());
+                }
+                PathOnly { path } => {
+                    let source = RustSource::locate(path);
+
+                    commands.push(source.command(source.module_filter().as_ref()).into());
+                }
+                NameOnly { name } => {
+                    let filter = Filter::Contains(name.to_string());
+
+                    commands.push(RustSource::default().command(Some(&filter)).into());
+                }
+            };
+        }
+
+        none_if_empty!(commands)
+    }
+}
+
+/// Finds the filter for the `#[test]` fn or `mod` enclosing the given line,
+/// falling back to the module of the file. Returns `None` if the whole target
+/// should be run.
+pub(super) fn find_line_filter(
+    config: &AdapterConfig,
+    source: &RustSource,
+    path: &TestFile,
+    line: u32,
+) -> Option<Filter> {
+    let test_index = match TestIndex::build(path, config) {
+        Ok(test_index) => test_index,
+        Err(_) => return source.module_filter(),
+    };
+    let enclosing = match test_index.enclosing(line) {
+        Some(enclosing) => enclosing,
+        None => return source.module_filter(),
+    };
+
+    let mut segments = source.module_path.clone();
+    segments.extend(
+        test_index
+            .ancestors(enclosing)
+            .iter()
+            .filter_map(|namespace| namespace.name())
+            .map(String::from),
+    );
+    segments.extend(enclosing.name().map(String::from));
+
+    if enclosing.is_test() {
+        Some(Filter::Exact(segments.join("::")))
+    } else {
+        Some(Filter::Module(segments.join("::")))
+    }
+}
+
+/// A filter on the paths of Rust tests.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum Filter {
+    /// Runs the test with exactly this path.
+    Exact(String),
+    /// Runs every test in the module with this path.
+    Module(String),
+    /// Runs every test whose path contains this string.
+    Contains(String),
+}
+
+/// Where a Rust source file lives in its Cargo package.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(super) struct RustSource {
+    /// The name of the package, from the nearest Cargo.toml.
+    pub(super) package: Option<String>,
+    /// The `--test` or `--bin` target the file is compiled into, if it is not
+    /// part of the library.
+    pub(super) target: Option<(&'static str, String)>,
+    /// The path of the module the file defines, within its target.
+    pub(super) module_path: Vec<String>,
+}
+
+impl RustSource {
+    /// Locates the file in its package, using the nearest Cargo.toml.
+    pub(super) fn locate(path: &TestFile) -> Self {
+        let file = match current_dir() {
+            Ok(cwd) => cwd.join(path),
+            Err(_) => return Self::default(),
+        };
+        let manifest = match file.parent().and_then(|dir| find_up(dir, "Cargo.toml")) {
+            Some(manifest) => manifest,
+            None => return Self::default(),
+        };
+        let relative = match manifest
+            .parent()
+            .and_then(|root| file.strip_prefix(root).ok())
+        {
+            Some(relative) => relative,
+            None => return Self::default(),
+        };
+
+        let (target, module_path) = target_and_module_path(relative);
+
+        Self {
+            package: package_name(&manifest),
+            target,
+            module_path,
+        }
+    }
+
+    /// Returns a filter for all of the tests in the file's module, or `None`
+    /// if the file is the root of its target.
+    pub(super) fn module_filter(&self) -> Option<Filter> {
+        if self.module_path.is_empty() {
+            None
+        } else {
+            Some(Filter::Module(self.module_path.join("::")))
+        }
+    }
+
+    /// Builds the `cargo test` command for the given filter.
+    fn command(&self, filter: Option<&Filter>) -> String {
+        let mut args = vec!["cargo".to_string(), "test".to_string()];
+
+        if let Some(package) = &self.package {
+            args.extend(["-p".to_string(), package.to_string()]);
+        }
+        if let Some((kind, name)) = &self.target {
+            args.extend([kind.to_string(), name.to_string()]);
+        }
+        match filter {
+            Some(Filter::Exact(name)) => {
+                args.extend([name.to_string(), "--".to_string(), "--exact".to_string()])
+            }
+            Some(Filter::Module(path)) => args.push(format!("{path}::")),
+            Some(Filter::Contains(name)) => args.push(name.to_string()),
+            None => {}
+        }
+
+        shell_words::join(args)
+    }
+}
+
+/// Maps a path relative to the package root onto the target it belongs to and
+/// its module path within that target, following Cargo's layout conventions.
+fn target_and_module_path(relative: &Path) -> (Option<(&'static str, String)>, Vec<String>) {
+    let mut segments = relative
+        .with_extension("")
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(segment) => segment.to_str().map(String::from),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if segments.last().map(String::as_str) == Some("mod") {
+        segments.pop();
+    }
+
+    // Targets like `tests/foo.rs` or `tests/foo/main.rs` are named `foo`.
+    let target_module_path = |rest: &[String]| -> Vec<String> {
+        rest.iter()
+            .filter(|segment| *segment != "main")
+            .cloned()
+            .collect()
+    };
+
+    match segments.as_slice() {
+        [tests, target, rest @ ..] if tests == "tests" => {
+            (Some(("--test", target.clone())), target_module_path(rest))
+        }
+        [src, bin, target, rest @ ..] if src == "src" && bin == "bin" => {
+            (Some(("--bin", target.clone())), target_module_path(rest))
+        }
+        [src, root] if src == "src" && (root == "lib" || root == "main") => (None, vec![]),
+        [src, rest @ ..] if src == "src" => (None, rest.to_vec()),
+        _ => (None, vec![]),
+    }
+}
+
+/// Reads the package name from a Cargo.toml, if it has one.
+fn package_name(manifest: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(manifest).ok()?;
+    let document = content.parse::<toml_edit::Document>().ok()?;
+
+    document
+        .get("package")?
+        .get("name")?
+        .as_str()
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_env;
+
+    const TEST_CONTENT: &str = r#"pub fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds() {
+        assert_eq!(add(1, 2), 3);
+    }
+
+    fn helper() {}
+
+    #[test]
+    #[should_panic]
+    fn panics() {
+        panic!();
+    }
+}
+"#;
+
+    fn write_package(env: &test_env::TestEnv) {
+        env.write_file("Cargo.toml", "[package]\nname = \"my-crate\"\n");
+        env.write_file("src/lib.rs", "mod math;\n");
+        env.write_file("src/math/ops.rs", TEST_CONTENT);
+    }
+
+    #[test]
+    fn selector_matches_rust_files() {
+        test_env::with(|env| {
+            write_package(env);
+            let adapter = CargoTest::default();
+
+            assert_eq!(
+                SelectorMatch::Exclusive,
+                adapter.selector_matches(&env.selector("src/math/ops.rs:11"))
+            );
+            assert_eq!(
+                SelectorMatch::Shared,
+                adapter.selector_matches(&env.selector("adds"))
+            );
+        })
+    }
+
+    #[test]
+    fn selector_does_not_match_name_only_outside_of_packages() {
+        test_env::with(|env| {
+            let adapter = CargoTest::default();
+
+            assert_eq!(
+                SelectorMatch::None,
+                adapter.selector_matches(&env.selector("adds"))
+            );
+        })
+    }
+
+    #[test]
+    fn selector_does_not_match_name_only_with_nextest_config() {
+        test_env::with(|env| {
+            write_package(env);
+            env.write_file(".config/nextest.toml", "");
+            let adapter = CargoTest::default();
+
+            assert_eq!(
+                SelectorMatch::None,
+                adapter.selector_matches(&env.selector("adds"))
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_path_with_line_number_in_test() {
+        test_env::with(|env| {
+            write_package(env);
+
+            assert_eq!(
+                env.commands(&CargoTest::default(), &["src/math/ops.rs:11"]),
+                vec!["cargo test -p my-crate math::ops::tests::adds -- --exact"]
+            );
+            assert_eq!(
+                env.commands(&CargoTest::default(), &["src/math/ops.rs:19"]),
+                vec!["cargo test -p my-crate math::ops::tests::panics -- --exact"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_path_with_line_number_between_tests() {
+        test_env::with(|env| {
+            write_package(env);
+
+            assert_eq!(
+                env.commands(&CargoTest::default(), &["src/math/ops.rs:14"]),
+                vec!["cargo test -p my-crate math::ops::tests::"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_path_with_line_number_outside_of_tests() {
+        test_env::with(|env| {
+            write_package(env);
+
+            assert_eq!(
+                env.commands(&CargoTest::default(), &["src/math/ops.rs:2"]),
+                vec!["cargo test -p my-crate math::ops::"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_path_only() {
+        test_env::with(|env| {
+            write_package(env);
+
+            assert_eq!(
+                env.commands(&CargoTest::default(), &["src/math/ops.rs"]),
+                vec!["cargo test -p my-crate math::ops::"]
+            );
+            assert_eq!(
+                env.commands(&CargoTest::default(), &["src/lib.rs"]),
+                vec!["cargo test -p my-crate"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_name_only() {
+        test_env::with(|env| {
+            write_package(env);
+
+            assert_eq!(
+                env.commands(&CargoTest::default(), &["adds"]),
+                vec!["cargo test adds"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_for_integration_tests() {
+        test_env::with(|env| {
+            write_package(env);
+            env.write_file(
+                "tests/it.rs",
+                "#[test]\nfn works() {\n    assert!(true);\n}\n",
+            );
+
+            assert_eq!(
+                env.commands(&CargoTest::default(), &["tests/it.rs:3"]),
+                vec!["cargo test -p my-crate --test it works -- --exact"]
+            );
+            assert_eq!(
+                env.commands(&CargoTest::default(), &["tests/it.rs"]),
+                vec!["cargo test -p my-crate --test it"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_uses_the_nearest_workspace_member() {
+        test_env::with(|env| {
+            env.write_file("Cargo.toml", "[workspace]\nmembers = [\"crates/*\"]\n");
+            env.write_file("crates/util/Cargo.toml", "[package]\nname = \"util\"\n");
+            env.write_file("crates/util/src/lib.rs", TEST_CONTENT);
+
+            assert_eq!(
+                env.commands(&CargoTest::default(), &["crates/util/src/lib.rs:11"]),
+                vec!["cargo test -p util tests::adds -- --exact"]
+            );
+        })
+    }
+}
@@ -0,0 +1,427 @@
+use super::{
+    cpp_gtest::{
+        ctest_command, ctest_listing, ctest_tests, find_test_binary, uses_ctest,
+        warn_missing_binary, BUILD_DIR,
+    },
+    SelectorMatch, TestAdapter,
+};
+use crate::{
+    config::{AdapterConfig, Config},
+    none_if_empty,
+    selector_match::{exclusive_or_none, shared_or_none},
+    test_file::TestFile,
+    test_index::TestIndex,
+    utils::is_in_file,
+    TestSelector,
+};
+use std::{borrow::Cow, fs::read_to_string};
+use TestSelector::*;
+
+/// The framework a test file is written for. Both declare tests with
+/// `TEST_CASE`, but select them with different command line options.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Framework {
+    Catch2,
+    Doctest,
+}
+
+impl Framework {
+    /// Detects the framework from the headers a test file includes.
+    fn of(path: &TestFile) -> Self {
+        match read_to_string(path) {
+            Ok(content) if content.contains("doctest") => Self::Doctest,
+            _ => Self::Catch2,
+        }
+    }
+
+    /// Detects the framework the project's CMake build pulls in.
+    fn of_project() -> Self {
+        if is_in_file("CMakeLists.txt", "doctest") {
+            Self::Doctest
+        } else {
+            Self::Catch2
+        }
+    }
+
+    /// Builds the arguments which run any of the given test cases, and within
+    /// them only the given (nested) sections.
+    fn test_case_args(self, test_cases: &[String], sections: &[String]) -> Vec<String> {
+        match self {
+            Self::Catch2 => {
+                let test_cases = test_cases
+                    .iter()
+                    .map(|test_case| escape_test_spec(test_case));
+                let mut args = vec![test_cases.collect::<Vec<_>>().join(",")];
+
+                for section in sections {
+                    args.extend(["-c".to_string(), section.to_string()]);
+                }
+                args
+            }
+            Self::Doctest => {
+                let test_cases = test_cases
+                    .iter()
+                    .map(|test_case| test_case.replace(',', r"\,"));
+                let mut args = vec![format!("-tc={}", test_cases.collect::<Vec<_>>().join(","))];
+
+                if !sections.is_empty() {
+                    let sections = sections.iter().map(|section| section.replace(',', r"\,"));
+                    args.push(format!("-sc={}", sections.collect::<Vec<_>>().join(",")));
+                }
+                args
+            }
+        }
+    }
+
+    /// Builds the arguments which run the test cases matching a name, or
+    /// carrying a tag if the name is a tag selector like `@slow`. doctest has
+    /// no tags, so its test suites are used instead.
+    fn name_args(self, name: &str) -> Vec<String> {
+        match (self, name.strip_prefix('@')) {
+            (Self::Catch2, Some(tag)) => vec![format!("[{tag}]")],
+            (Self::Catch2, None) => vec![format!("*{}*", escape_test_spec(name))],
+            (Self::Doctest, Some(tag)) => vec![format!("-ts={tag}")],
+            (Self::Doctest, None) => vec![format!("-tc=*{}*", name.replace(',', r"\,"))],
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CppCatch2 {
+    config: AdapterConfig,
+}
+
+impl CppCatch2 {
+    /// Creates a new CppCatch2 adapter.
+    pub const fn new(config: AdapterConfig) -> Self {
+        Self { config }
+    }
+
+    /// Finds the names of the `TEST_CASE` and the nested `SECTION`s (or
+    /// `SUBCASE`s) enclosing the given line. If the line is outside of a test
+    /// case, every test case in the file is returned.
+    fn find_test_cases(&self, path: &TestFile, line: Option<u32>) -> (Vec<String>, Vec<String>) {
+        let test_index = match TestIndex::build(path, &self.config) {
+            Ok(test_index) => test_index,
+            Err(_) => return (vec![], vec![]),
+        };
+
+        let enclosing = match line.and_then(|line| test_index.enclosing(line)) {
+            Some(enclosing) => enclosing,
+            None => {
+                let test_cases = test_index
+                    .top_level()
+                    .into_iter()
+                    .filter_map(|test_case| test_case.name().map(String::from));
+
+                return (test_cases.collect(), vec![]);
+            }
+        };
+
+        let mut names = test_index
+            .ancestors(enclosing)
+            .into_iter()
+            .chain([enclosing])
+            .filter_map(|entry| entry.name().map(String::from))
+            .collect::<Vec<_>>();
+        let sections = names.split_off(1.min(names.len()));
+
+        (names, sections)
+    }
+}
+
+impl Default for CppCatch2 {
+    fn default() -> Self {
+        Self::new(Config::default().cpp_catch2.clone())
+    }
+}
+
+impl TestAdapter for CppCatch2 {
+    fn selector_matches(&self, selector: &TestSelector) -> SelectorMatch {
+        match selector {
+            PathWithLineNumber { path, .. } | PathOnly { path } => exclusive_or_none(
+                path.matches(&self.config.file_patterns)
+                    && !self.find_test_cases(path, None).0.is_empty(),
+            ),
+            NameOnly { .. } => shared_or_none(uses_ctest()),
+        }
+    }
+
+    fn collect_commands(&self, selectors: &[&TestSelector]) -> Option<Vec<Cow<'_, str>>> {
+        let mut commands: Vec<Cow<str>> = vec![];
+        let listing = ctest_listing();
+
+        for selector in selectors {
+            let (path, line) = match selector {
+                PathWithLineNumber { path, line } => (path, Some(*line)),
+                PathOnly { path } => (path, None),
+                NameOnly { name } => {
+                    commands.extend(
+                        name_commands(listing.as_deref(), name)
+                            .into_iter()
+                            .map(Cow::from),
+                    );
+                    continue;
+                }
+            };
+
+            let (test_cases, sections) = self.find_test_cases(path, line);
+            let binary = listing
+                .as_deref()
+                .and_then(|listing| find_test_binary(listing, path, &test_cases));
+
+            let command = match binary {
+                Some(binary) => {
+                    let mut args = vec![binary];
+                    args.extend(Framework::of(path).test_case_args(&test_cases, &sections));
+                    shell_words::join(args)
+                }
+                None => {
+                    if listing.is_some() {
+                        warn_missing_binary(path);
+                    }
+
+                    let names = test_cases.iter().map(|test_case| regex::escape(test_case));
+                    ctest_command(&format!("^({})$", names.collect::<Vec<_>>().join("|")))
+                }
+            };
+
+            commands.push(command.into());
+        }
+
+        none_if_empty!(commands)
+    }
+}
+
+/// Builds the commands which run the test cases matching a name or tag, with
+/// each Catch2 or doctest binary CTest knows about. Falls back to running the
+/// tests with a tag through CTest, which labels tests with their tags when
+/// `catch_discover_tests` is given `ADD_TAGS_AS_LABELS`. Plain names are left
+/// to the GoogleTest adapter, which already runs them through CTest.
+fn name_commands(listing: Option<&str>, name: &str) -> Vec<String> {
+    let mut binaries = listing
+        .map(ctest_tests)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|ctest_test| !ctest_test.runs_gtest)
+        .map(|ctest_test| ctest_test.binary)
+        .collect::<Vec<_>>();
+    binaries.sort();
+    binaries.dedup();
+
+    if binaries.is_empty() {
+        return name
+            .strip_prefix('@')
+            .map(|tag| shell_words::join(["ctest", "--test-dir", BUILD_DIR, "-L", tag]))
+            .into_iter()
+            .collect();
+    }
+
+    let framework = Framework::of_project();
+
+    binaries
+        .into_iter()
+        .map(|binary| {
+            let mut args = vec![binary];
+            args.extend(framework.name_args(name));
+            shell_words::join(args)
+        })
+        .collect()
+}
+
+/// Escapes the characters Catch2 gives special meaning to in a test spec.
+fn escape_test_spec(name: &str) -> String {
+    name.chars()
+        .flat_map(|c| match c {
+            '\\' | '[' | ']' | '*' | ',' | '~' => vec!['\\', c],
+            c => vec![c],
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_env;
+
+    const CATCH2_CONTENT: &str = r#"#include <catch2/catch_test_macros.hpp>
+#include "stack.h"
+
+TEST_CASE("stacks can grow", "[stack][fast]") {
+  Stack stack;
+
+  SECTION("pushing an item") {
+    stack.push(1);
+
+    SECTION("then popping it") {
+      REQUIRE(stack.pop() == 1);
+    }
+  }
+}
+
+TEST_CASE_METHOD(StackFixture, "stacks, when empty", "[stack]") {
+  REQUIRE(stack.empty());
+}
+"#;
+
+    const DOCTEST_CONTENT: &str = r#"#include <doctest/doctest.h>
+
+TEST_CASE("stacks can grow") {
+  SUBCASE("pushing an item") {
+    CHECK(Stack().push(1).size() == 1);
+  }
+}
+"#;
+
+    fn args(items: &[&str]) -> Vec<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    #[test]
+    fn selector_matches_catch2_and_doctest_files() {
+        test_env::with(|env| {
+            env.write_file("tests/stack_test.cpp", CATCH2_CONTENT);
+            env.write_file("tests/queue_test.cpp", DOCTEST_CONTENT);
+            env.write_file("tests/parser_test.cc", "TEST(ParserTest, Parses) {\n}\n");
+            let adapter = CppCatch2::default();
+
+            assert_eq!(
+                SelectorMatch::Exclusive,
+                adapter.selector_matches(&env.selector("tests/stack_test.cpp:8"))
+            );
+            assert_eq!(
+                SelectorMatch::Exclusive,
+                adapter.selector_matches(&env.selector("tests/queue_test.cpp:5"))
+            );
+            assert_eq!(
+                SelectorMatch::None,
+                adapter.selector_matches(&env.selector("tests/parser_test.cc"))
+            );
+            assert_eq!(
+                SelectorMatch::None,
+                adapter.selector_matches(&env.selector("@fast"))
+            );
+
+            env.write_file("build/CTestTestfile.cmake", "");
+
+            assert_eq!(
+                SelectorMatch::Shared,
+                adapter.selector_matches(&env.selector("@fast"))
+            );
+        })
+    }
+
+    #[test]
+    fn find_test_cases_resolves_sections() {
+        test_env::with(|env| {
+            env.write_file("tests/stack_test.cpp", CATCH2_CONTENT);
+            let adapter = CppCatch2::default();
+            let path = "tests/stack_test.cpp".into();
+
+            assert_eq!(
+                adapter.find_test_cases(&path, Some(11)),
+                (
+                    args(&["stacks can grow"]),
+                    args(&["pushing an item", "then popping it"])
+                )
+            );
+            assert_eq!(
+                adapter.find_test_cases(&path, Some(5)),
+                (args(&["stacks can grow"]), vec![])
+            );
+            assert_eq!(
+                adapter.find_test_cases(&path, Some(1)),
+                (args(&["stacks can grow", "stacks, when empty"]), vec![])
+            );
+        })
+    }
+
+    #[test]
+    fn framework_args() {
+        let test_cases = args(&["stacks, when empty"]);
+        let sections = args(&["pushing an item", "then popping it"]);
+
+        assert_eq!(
+            Framework::Catch2.test_case_args(&test_cases, &sections),
+            args(&[
+                r"stacks\, when empty",
+                "-c",
+                "pushing an item",
+                "-c",
+                "then popping it"
+            ])
+        );
+        assert_eq!(
+            Framework::Doctest.test_case_args(&test_cases, &sections),
+            args(&[
+                r"-tc=stacks\, when empty",
+                "-sc=pushing an item,then popping it"
+            ])
+        );
+        assert_eq!(Framework::Catch2.name_args("@fast"), args(&["[fast]"]));
+        assert_eq!(Framework::Catch2.name_args("grow"), args(&["*grow*"]));
+        assert_eq!(Framework::Doctest.name_args("@fast"), args(&["-ts=fast"]));
+        assert_eq!(Framework::Doctest.name_args("grow"), args(&["-tc=*grow*"]));
+    }
+
+    #[test]
+    fn framework_of_test_file() {
+        test_env::with(|env| {
+            env.write_file("tests/stack_test.cpp", CATCH2_CONTENT);
+            env.write_file("tests/queue_test.cpp", DOCTEST_CONTENT);
+
+            assert_eq!(
+                Framework::of(&"tests/stack_test.cpp".into()),
+                Framework::Catch2
+            );
+            assert_eq!(
+                Framework::of(&"tests/queue_test.cpp".into()),
+                Framework::Doctest
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_without_a_test_binary() {
+        test_env::with(|env| {
+            env.write_file("tests/stack_test.cpp", CATCH2_CONTENT);
+
+            assert_eq!(
+                env.commands(&CppCatch2::default(), &["tests/stack_test.cpp:11"]),
+                vec!["ctest --test-dir build -R '^(stacks can grow)$'"]
+            );
+            assert_eq!(
+                env.commands(&CppCatch2::default(), &["tests/stack_test.cpp"]),
+                vec!["ctest --test-dir build -R '^(stacks can grow|stacks, when empty)$'"]
+            );
+            assert_eq!(
+                env.commands(&CppCatch2::default(), &["@fast"]),
+                vec!["ctest --test-dir build -L fast"]
+            );
+            assert!(name_commands(None, "grow").is_empty());
+        })
+    }
+
+    #[test]
+    fn name_commands_with_test_binaries() {
+        test_env::with(|env| {
+            let listing = r#"{"tests": [
+                {"command": ["/opt/gtests", "--gtest_filter=A.b"], "name": "A.b"},
+                {"command": ["/opt/stack_tests", "stacks can grow"], "name": "stacks can grow"},
+                {"command": ["/opt/stack_tests", "stacks, when empty"], "name": "stacks, when empty"}
+            ]}"#;
+
+            assert_eq!(
+                name_commands(Some(listing), "@fast"),
+                vec!["/opt/stack_tests '[fast]'"]
+            );
+
+            env.write_file("CMakeLists.txt", "find_package(doctest REQUIRED)\n");
+
+            assert_eq!(
+                name_commands(Some(listing), "@fast"),
+                vec!["/opt/stack_tests '-ts=fast'"]
+            );
+        })
+    }
+}
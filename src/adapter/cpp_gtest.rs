@@ -0,0 +1,383 @@
+use super::{SelectorMatch, TestAdapter};
+use crate::{
+    config::{AdapterConfig, Config},
+    none_if_empty,
+    selector_match::{exclusive_or_none, shared_or_none},
+    test_file::TestFile,
+    test_index::TestIndex,
+    utils::file_exists,
+    TestSelector,
+};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::{borrow::Cow, env::current_dir, path::Path, process::Command};
+use TestSelector::*;
+
+/// The CMake build directory CTest is run against.
+pub(super) const BUILD_DIR: &str = "build";
+
+lazy_static! {
+    /// Matches a test in `ctest --show-only=json-v1` output, capturing the
+    /// executable it runs, the rest of its command and its name. CTest writes
+    /// the keys of each test in sorted order, so `command` always comes before
+    /// `name`.
+    static ref CTEST_TEST: Regex = Regex::new(
+        r#""command"\s*:\s*\[\s*"(?P<binary>(?:[^"\\]|\\.)*)"(?P<arguments>[^\]]*)\]\s*,\s*"name"\s*:\s*"(?P<name>(?:[^"\\]|\\.)*)""#
+    )
+    .unwrap();
+}
+
+/// A test listed by CTest.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct CtestTest {
+    /// The name CTest knows the test by.
+    pub name: String,
+    /// The executable the test runs, relative to the current directory if it
+    /// is within it.
+    pub binary: String,
+    /// Whether the test runs a single gtest, as `gtest_discover_tests` does.
+    pub runs_gtest: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CppGTest {
+    config: AdapterConfig,
+}
+
+impl CppGTest {
+    /// Creates a new CppGTest adapter.
+    pub const fn new(config: AdapterConfig) -> Self {
+        Self { config }
+    }
+
+    /// Finds the `Suite.Name` of the `TEST` or `TEST_F` enclosing the given
+    /// line, or of every test in the file if the line is outside of one.
+    fn find_tests(&self, path: &TestFile, line: Option<u32>) -> Vec<String> {
+        let test_index = match TestIndex::build(path, &self.config) {
+            Ok(test_index) => test_index,
+            Err(_) => return vec![],
+        };
+
+        let entries = match line.and_then(|line| test_index.enclosing(line)) {
+            Some(enclosing) => vec![enclosing],
+            None => test_index.top_level(),
+        };
+
+        entries
+            .into_iter()
+            .filter(|entry| entry.is_test())
+            .filter_map(|entry| entry.name())
+            .map(gtest_name)
+            .collect()
+    }
+}
+
+impl Default for CppGTest {
+    fn default() -> Self {
+        Self::new(Config::default().cpp_gtest.clone())
+    }
+}
+
+impl TestAdapter for CppGTest {
+    fn selector_matches(&self, selector: &TestSelector) -> SelectorMatch {
+        match selector {
+            PathWithLineNumber { path, .. } | PathOnly { path } => exclusive_or_none(
+                path.matches(&self.config.file_patterns) && !self.find_tests(path, None).is_empty(),
+            ),
+            NameOnly { .. } => shared_or_none(uses_ctest()),
+        }
+    }
+
+    fn collect_commands(&self, selectors: &[&TestSelector]) -> Option<Vec<Cow<'_, str>>> {
+        let mut commands: Vec<Cow<str>> = vec![];
+        let listing = ctest_listing();
+
+        for selector in selectors {
+            let (path, tests) = match selector {
+                PathWithLineNumber { path, line } => (path, self.find_tests(path, Some(*line))),
+                PathOnly { path } => (path, self.find_tests(path, None)),
+                NameOnly { name } => {
+                    commands.push(ctest_command(&regex::escape(name)).into());
+                    continue;
+                }
+            };
+
+            let binary = listing
+                .as_deref()
+                .and_then(|listing| find_test_binary(listing, path, &tests));
+
+            let command = match binary {
+                Some(binary) => {
+                    let filter = format!("--gtest_filter={}", tests.join(":"));
+                    shell_words::join([binary, filter])
+                }
+                None => {
+                    if listing.is_some() {
+                        warn_missing_binary(path);
+                    }
+
+                    let names = tests.iter().map(|test| regex::escape(test));
+                    ctest_command(&format!("^({})$", names.collect::<Vec<_>>().join("|")))
+                }
+            };
+
+            commands.push(command.into());
+        }
+
+        none_if_empty!(commands)
+    }
+}
+
+/// Returns true if the project has a CMake build directory with tests in it.
+pub(super) fn uses_ctest() -> bool {
+    file_exists(Path::new(BUILD_DIR).join("CTestTestfile.cmake"))
+}
+
+/// Turns the `Suite, Name` arguments of a test macro into a gtest name.
+fn gtest_name(arguments: &str) -> String {
+    arguments
+        .split(',')
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Runs the tests whose names match the pattern with CTest.
+pub(super) fn ctest_command(pattern: &str) -> String {
+    shell_words::join(["ctest", "--test-dir", BUILD_DIR, "-R", pattern])
+}
+
+/// Lists the tests CTest knows about, as JSON. Returns `None` if there is no
+/// build directory, or if CTest could not be run.
+pub(super) fn ctest_listing() -> Option<String> {
+    if !uses_ctest() {
+        return None;
+    }
+
+    let output = Command::new("ctest")
+        .args(["--show-only=json-v1", "--test-dir", BUILD_DIR])
+        .output()
+        .ok()?;
+
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Reads the tests out of a CTest listing.
+pub(super) fn ctest_tests(listing: &str) -> Vec<CtestTest> {
+    CTEST_TEST
+        .captures_iter(listing)
+        .map(|captures| CtestTest {
+            name: unescape_json(&captures["name"]),
+            binary: relative_to_cwd(unescape_json(&captures["binary"])),
+            runs_gtest: captures["arguments"].contains("--gtest_filter="),
+        })
+        .collect()
+}
+
+/// Finds the test binary that runs the tests of a file in a CTest listing.
+/// Tests registered with `gtest_discover_tests` are named after the gtest they
+/// run, and Catch2 and doctest discover theirs the same way. A binary added
+/// with a plain `add_test` is instead found by its name, which conventionally
+/// follows the file (`parser_tests` for `parser_test.cc`), or by being the only
+/// one there is.
+pub(super) fn find_test_binary(listing: &str, path: &TestFile, tests: &[String]) -> Option<String> {
+    let ctest_tests = ctest_tests(listing);
+
+    if let Some(ctest_test) = ctest_tests
+        .iter()
+        .find(|ctest_test| tests.contains(&ctest_test.name))
+    {
+        return Some(ctest_test.binary.clone());
+    }
+
+    let stem = path.as_ref().file_stem()?.to_string_lossy();
+    let names = [stem.to_string(), format!("{stem}s")];
+    let named_after_file = ctest_tests.iter().find(|ctest_test| {
+        let binary_name = Path::new(&ctest_test.binary)
+            .file_stem()
+            .map(|name| name.to_string_lossy().to_string());
+
+        names.contains(&ctest_test.name) || binary_name.is_some_and(|name| names.contains(&name))
+    });
+
+    if let Some(ctest_test) = named_after_file {
+        return Some(ctest_test.binary.clone());
+    }
+
+    let mut binaries = ctest_tests.into_iter().map(|ctest_test| ctest_test.binary);
+    let only_binary = binaries.next()?;
+
+    binaries
+        .all(|binary| binary == only_binary)
+        .then_some(only_binary)
+}
+
+/// Warns that the tests of a file will be run by name through CTest, which
+/// finds nothing unless CTest knows the tests by their gtest names.
+pub(super) fn warn_missing_binary(path: &TestFile) {
+    eprintln!(
+        "Could not find the test binary for {} in CTest, running its tests by name",
+        path.to_string_lossy()
+    );
+}
+
+fn unescape_json(text: &str) -> String {
+    text.replace(r"\\", r"\").replace(r#"\""#, r#"""#)
+}
+
+fn relative_to_cwd(binary: String) -> String {
+    let relative = current_dir().ok().and_then(|cwd| {
+        Path::new(&binary)
+            .strip_prefix(cwd)
+            .ok()
+            .map(Path::to_path_buf)
+    });
+
+    match relative {
+        Some(relative) => format!("./{}", relative.to_string_lossy()),
+        None => binary,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_env;
+
+    const TEST_CONTENT: &str = r#"#include <gtest/gtest.h>
+#include "parser.h"
+
+TEST(ParserTest, ParsesEmptyInput) {
+  EXPECT_TRUE(Parse("").empty());
+}
+
+class TokenizerTest : public ::testing::Test {};
+
+TEST_F(TokenizerTest, SplitsWords) {
+  EXPECT_EQ(Tokenize("a b").size(), 2);
+}
+"#;
+
+    const CTEST_LISTING: &str = r#"{
+  "kind" : "ctestInfo",
+  "tests" :
+  [
+    {
+      "backtrace" : 5,
+      "command" :
+      [
+        "/home/me/ext/build/tests/parser_tests",
+        "--gtest_filter=ParserTest.ParsesEmptyInput",
+        "--gtest_also_run_disabled_tests"
+      ],
+      "name" : "ParserTest.ParsesEmptyInput",
+      "properties" : []
+    }
+  ],
+  "version" : { "major" : 1, "minor" : 0 }
+}"#;
+
+    #[test]
+    fn selector_matches_gtest_files() {
+        test_env::with(|env| {
+            env.write_file("tests/parser_test.cc", TEST_CONTENT);
+            env.write_file("tests/helpers.cc", "int helper() { return 1; }\n");
+            let adapter = CppGTest::default();
+
+            assert_eq!(
+                SelectorMatch::Exclusive,
+                adapter.selector_matches(&env.selector("tests/parser_test.cc:5"))
+            );
+            assert_eq!(
+                SelectorMatch::None,
+                adapter.selector_matches(&env.selector("tests/helpers.cc"))
+            );
+            assert_eq!(
+                SelectorMatch::None,
+                adapter.selector_matches(&env.selector("ParsesEmptyInput"))
+            );
+
+            env.write_file("build/CTestTestfile.cmake", "");
+
+            assert_eq!(
+                SelectorMatch::Shared,
+                adapter.selector_matches(&env.selector("ParsesEmptyInput"))
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_without_a_test_binary() {
+        test_env::with(|env| {
+            env.write_file("tests/parser_test.cc", TEST_CONTENT);
+
+            assert_eq!(
+                env.commands(&CppGTest::default(), &["tests/parser_test.cc:5"]),
+                vec![r"ctest --test-dir build -R '^(ParserTest\.ParsesEmptyInput)$'"]
+            );
+            assert_eq!(
+                env.commands(&CppGTest::default(), &["tests/parser_test.cc:11"]),
+                vec![r"ctest --test-dir build -R '^(TokenizerTest\.SplitsWords)$'"]
+            );
+            assert_eq!(
+                env.commands(&CppGTest::default(), &["tests/parser_test.cc"]),
+                vec![
+                    r"ctest --test-dir build -R '^(ParserTest\.ParsesEmptyInput|TokenizerTest\.SplitsWords)$'"
+                ]
+            );
+            assert_eq!(
+                env.commands(&CppGTest::default(), &["SplitsWords"]),
+                vec!["ctest --test-dir build -R SplitsWords"]
+            );
+        })
+    }
+
+    #[test]
+    fn find_test_binary_in_ctest_listing() {
+        let tests = ["ParserTest.ParsesEmptyInput".to_string()];
+
+        assert_eq!(
+            find_test_binary(CTEST_LISTING, &"tests/lexer_test.cc".into(), &tests),
+            Some("/home/me/ext/build/tests/parser_tests".to_string())
+        );
+        assert_eq!(
+            find_test_binary(
+                CTEST_LISTING,
+                &"tests/lexer_test.cc".into(),
+                &["LexerTest.Lexes".to_string()]
+            ),
+            Some("/home/me/ext/build/tests/parser_tests".to_string())
+        );
+        assert!(ctest_tests(CTEST_LISTING)[0].runs_gtest);
+    }
+
+    #[test]
+    fn find_test_binary_added_with_add_test() {
+        let listing = r#"{
+  "tests" :
+  [
+    {
+      "command" : [ "/home/me/ext/build/tests/lexer_tests" ],
+      "name" : "lexer_tests"
+    },
+    {
+      "command" : [ "/home/me/ext/build/tests/parser_tests" ],
+      "name" : "parser_tests"
+    }
+  ]
+}"#;
+        let tests = ["ParserTest.ParsesEmptyInput".to_string()];
+
+        assert_eq!(
+            find_test_binary(listing, &"tests/parser_test.cc".into(), &tests),
+            Some("/home/me/ext/build/tests/parser_tests".to_string())
+        );
+        assert_eq!(
+            find_test_binary(listing, &"tests/tokenizer_test.cc".into(), &tests),
+            None
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use super::{SelectorMatch, TestAdapter};
+use crate::{
+    config::{AdapterConfig, Config},
+    memoize_string, none_if_empty,
+    selector_match::{exclusive_or_none, shared_or_none},
+    test_file::TestFile,
+    test_index::TestIndex,
+    utils::{file_exists, is_in_file},
+    TestSelector,
+};
+use std::borrow::Cow;
+use TestSelector::*;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct DartTest {
+    config: AdapterConfig,
+}
+
+impl DartTest {
+    /// Creates a new DartTest adapter.
+    pub const fn new(config: AdapterConfig) -> Self {
+        Self { config }
+    }
+
+    /// Finds the full name of the `test` or `group` enclosing the given line,
+    /// made of the names of the groups it is nested in. Returns `None` if the
+    /// whole file should be run.
+    fn find_plain_name(&self, path: &TestFile, line: u32) -> Option<String> {
+        let test_index = TestIndex::build(path, &self.config).ok()?;
+        let enclosing = test_index.enclosing(line)?;
+
+        let full_name = test_index
+            .ancestors(enclosing)
+            .into_iter()
+            .chain([enclosing])
+            .filter_map(|entry| entry.name())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Some(full_name)
+    }
+}
+
+impl Default for DartTest {
+    fn default() -> Self {
+        Self::new(Config::default().dart_test.clone())
+    }
+}
+
+impl TestAdapter for DartTest {
+    fn selector_matches(&self, selector: &TestSelector) -> SelectorMatch {
+        match selector {
+            PathWithLineNumber { path, .. } | PathOnly { path } => {
+                exclusive_or_none(path.matches(&self.config.file_patterns))
+            }
+            NameOnly { .. } => shared_or_none(file_exists("pubspec.yaml")),
+        }
+    }
+
+    fn collect_commands(&self, selectors: &[&TestSelector]) -> Option<Vec<Cow<'_, str>>> {
+        let mut commands: Vec<Cow<str>> = vec![];
+
+        for selector in selectors {
+            let mut args = vec![];
+
+            match selector {
+                PathWithLineNumber { path, line } => {
+                    args.push(path.to_string_lossy().to_string());
+
+                    if let Some(name) = self.find_plain_name(path, *line) {
+                        args.extend(["--plain-name".to_string(), name]);
+                    }
+                }
+                PathOnly { path } => args.push(path.to_string_lossy().to_string()),
+                NameOnly { name } => args.extend(["--plain-name".to_string(), name.to_string()]),
+            };
+
+            commands.push(format!("{} {}", dart_command(), shell_words::join(args)).into());
+        }
+
+        none_if_empty!(commands)
+    }
+}
+
+/// Flutter packages must be tested with `flutter test`, which sets up the
+/// Flutter test environment `dart test` lacks.
+fn dart_command() -> Cow<'static, str> {
+    memoize_string!({
+        if is_in_file("pubspec.yaml", "sdk: flutter") {
+            "flutter test"
+        } else {
+            "dart test"
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_env;
+
+    const TEST_CONTENT: &str = r#"import 'package:test/test.dart';
+
+void main() {
+  group('Counter', () {
+    test('starts at zero', () {
+      expect(Counter().value, 0);
+    });
+
+    group("when incremented", () {
+      test('is one', () async {
+        expect((Counter()..increment()).value, 1);
+      });
+    });
+  });
+}
+"#;
+
+    const FLUTTER_PUBSPEC: &str = r#"name: counter
+
+dependencies:
+  flutter:
+    sdk: flutter
+"#;
+
+    #[test]
+    fn selector_matches_test_files() {
+        test_env::with(|env| {
+            env.write_file("test/counter_test.dart", TEST_CONTENT);
+            env.write_file("lib/counter.dart", "");
+            let adapter = DartTest::default();
+
+            assert_eq!(
+                SelectorMatch::Exclusive,
+                adapter.selector_matches(&env.selector("test/counter_test.dart:6"))
+            );
+            assert_eq!(
+                SelectorMatch::None,
+                adapter.selector_matches(&env.selector("lib/counter.dart"))
+            );
+            assert_eq!(
+                SelectorMatch::None,
+                adapter.selector_matches(&env.selector("is one"))
+            );
+
+            env.write_file("pubspec.yaml", "name: counter\n");
+
+            assert_eq!(
+                SelectorMatch::Shared,
+                adapter.selector_matches(&env.selector("is one"))
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_path_with_line_number() {
+        test_env::with(|env| {
+            env.write_file("test/counter_test.dart", TEST_CONTENT);
+
+            assert_eq!(
+                env.commands(&DartTest::default(), &["test/counter_test.dart:6"]),
+                vec!["dart test test/counter_test.dart --plain-name 'Counter starts at zero'"]
+            );
+            assert_eq!(
+                env.commands(&DartTest::default(), &["test/counter_test.dart:11"]),
+                vec!["dart test test/counter_test.dart --plain-name 'Counter when incremented is one'"]
+            );
+            assert_eq!(
+                env.commands(&DartTest::default(), &["test/counter_test.dart:8"]),
+                vec!["dart test test/counter_test.dart --plain-name Counter"]
+            );
+            assert_eq!(
+                env.commands(&DartTest::default(), &["test/counter_test.dart:1"]),
+                vec!["dart test test/counter_test.dart"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_with_flutter() {
+        test_env::with(|env| {
+            env.write_file("pubspec.yaml", FLUTTER_PUBSPEC);
+            env.write_file("test/counter_test.dart", TEST_CONTENT);
+
+            assert_eq!(
+                env.commands(&DartTest::default(), &["test/counter_test.dart:6"]),
+                vec!["flutter test test/counter_test.dart --plain-name 'Counter starts at zero'"]
+            );
+            assert_eq!(
+                env.commands(&DartTest::default(), &["is one"]),
+                vec!["flutter test --plain-name 'is one'"]
+            );
+        })
+    }
+}
@@ -0,0 +1,168 @@
+use super::{SelectorMatch, TestAdapter};
+use crate::{
+    config::{AdapterConfig, Config},
+    none_if_empty,
+    selector_match::{exclusive_or_none, shared_or_none},
+    test_file::TestFile,
+    test_index::TestIndex,
+    utils::file_exists,
+    TestSelector,
+};
+use std::borrow::Cow;
+use TestSelector::*;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct DenoTest {
+    config: AdapterConfig,
+}
+
+impl DenoTest {
+    /// Creates a new DenoTest adapter.
+    pub const fn new(config: AdapterConfig) -> Self {
+        Self { config }
+    }
+
+    /// Finds the `--filter` for the test enclosing the given line. Deno only
+    /// filters on top-level tests, since `describe` blocks and their `it`
+    /// cases run as steps of one test, so this is the name of the outermost
+    /// block, anchored so that tests whose names contain it aren't run too.
+    /// Returns `None` if the whole file should be run.
+    fn find_filter(&self, path: &TestFile, line: u32) -> Option<String> {
+        let test_index = TestIndex::build(path, &self.config).ok()?;
+        let enclosing = test_index.enclosing(line)?;
+
+        let ancestors = test_index.ancestors(enclosing);
+        let outermost = ancestors.first().unwrap_or(&enclosing);
+
+        outermost
+            .name()
+            .map(|name| format!("/^{}$/", regex::escape(name)))
+    }
+}
+
+impl Default for DenoTest {
+    fn default() -> Self {
+        Self::new(Config::default().deno_test.clone())
+    }
+}
+
+impl TestAdapter for DenoTest {
+    fn selector_matches(&self, selector: &TestSelector) -> SelectorMatch {
+        match selector {
+            PathWithLineNumber { path, .. } | PathOnly { path } => {
+                exclusive_or_none(uses_deno() && path.matches(&self.config.file_patterns))
+            }
+            NameOnly { .. } => shared_or_none(uses_deno()),
+        }
+    }
+
+    fn collect_commands(&self, selectors: &[&TestSelector]) -> Option<Vec<Cow<'_, str>>> {
+        let mut commands: Vec<Cow<str>> = vec![];
+
+        for selector in selectors {
+            let mut args = vec!["deno".to_string(), "test".to_string()];
+
+            match selector {
+                PathWithLineNumber { path, line } => {
+                    if let Some(filter) = self.find_filter(path, *line) {
+                        args.extend(["--filter".to_string(), filter]);
+                    }
+                    args.push(path.to_string_lossy().to_string());
+                }
+                PathOnly { path } => args.push(path.to_string_lossy().to_string()),
+                NameOnly { name } => args.extend(["--filter".to_string(), name.to_string()]),
+            };
+
+            commands.push(shell_words::join(args).into());
+        }
+
+        none_if_empty!(commands)
+    }
+}
+
+/// Returns true if the project is configured for Deno.
+pub(super) fn uses_deno() -> bool {
+    file_exists("deno.json") || file_exists("deno.jsonc")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_env;
+
+    const TEST_CONTENT: &str = r#"import { assertEquals } from "jsr:@std/assert";
+import { describe, it } from "jsr:@std/testing/bdd";
+
+Deno.test("adds numbers", () => {
+  assertEquals(1 + 1, 2);
+});
+
+describe("math", () => {
+  it("subtracts", () => {
+    assertEquals(2 - 1, 1);
+  });
+});
+
+Deno.test("adds numbers (with carry)", () => {
+  assertEquals(5 + 5, 10);
+});
+"#;
+
+    #[test]
+    fn selector_matches_with_deno_config() {
+        for file in ["deno.json", "deno.jsonc"] {
+            test_env::with(|env| {
+                env.write_file("math_test.ts", TEST_CONTENT);
+                let adapter = DenoTest::default();
+                let selector = env.selector("math_test.ts:5");
+
+                assert_eq!(SelectorMatch::None, adapter.selector_matches(&selector));
+
+                env.write_file(file, "{}");
+
+                assert_eq!(
+                    SelectorMatch::Exclusive,
+                    adapter.selector_matches(&selector)
+                );
+                assert_eq!(
+                    SelectorMatch::Shared,
+                    adapter.selector_matches(&env.selector("adds numbers"))
+                );
+            })
+        }
+    }
+
+    #[test]
+    fn collect_commands_path_with_line_number() {
+        test_env::with(|env| {
+            env.write_file("math_test.ts", TEST_CONTENT);
+
+            assert_eq!(
+                env.commands(&DenoTest::default(), &["math_test.ts:5"]),
+                vec!["deno test --filter '/^adds numbers$/' math_test.ts"]
+            );
+            assert_eq!(
+                env.commands(&DenoTest::default(), &["math_test.ts:10"]),
+                vec!["deno test --filter '/^math$/' math_test.ts"]
+            );
+            assert_eq!(
+                env.commands(&DenoTest::default(), &["math_test.ts:16"]),
+                vec![r"deno test --filter '/^adds numbers \(with carry\)$/' math_test.ts"]
+            );
+            assert_eq!(
+                env.commands(&DenoTest::default(), &["math_test.ts:1"]),
+                vec!["deno test math_test.ts"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_name_only() {
+        test_env::with(|env| {
+            assert_eq!(
+                env.commands(&DenoTest::default(), &["adds numbers"]),
+                vec!["deno test --filter 'adds numbers'"]
+            );
+        })
+    }
+}
@@ -0,0 +1,358 @@
+use super::{SelectorMatch, TestAdapter};
+use crate::{
+    config::{AdapterConfig, Config},
+    none_if_empty,
+    selector_match::{exclusive_or_none, shared_or_none},
+    test_file::TestFile,
+    test_index::TestIndex,
+    TestSelector,
+};
+use std::{
+    borrow::Cow,
+    env::current_dir,
+    fs::{read_dir, read_to_string},
+    path::{Path, PathBuf},
+};
+use TestSelector::*;
+
+/// The extensions of the project and solution files `dotnet test` accepts.
+const PROJECT_EXTENSIONS: [&str; 3] = ["csproj", "fsproj", "sln"];
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct DotnetTest {
+    config: AdapterConfig,
+}
+
+impl DotnetTest {
+    /// Creates a new DotnetTest adapter.
+    pub const fn new(config: AdapterConfig) -> Self {
+        Self { config }
+    }
+
+    /// Finds the `--filter` for the test method or class enclosing the given
+    /// line, falling back to the classes in the file.
+    fn find_filter(&self, path: &TestFile, line: u32) -> Option<String> {
+        let test_index = TestIndex::build(path, &self.config).ok()?;
+        let enclosing = match test_index.enclosing(line) {
+            Some(enclosing) => enclosing,
+            None => return self.find_file_filter(path),
+        };
+
+        let mut classes = test_index
+            .ancestors(enclosing)
+            .iter()
+            .filter_map(|class| class.name())
+            .collect::<Vec<_>>();
+
+        if !enclosing.is_test() {
+            classes.extend(enclosing.name());
+        }
+
+        let mut name = namespace(path).into_iter().collect::<Vec<_>>();
+        name.extend((!classes.is_empty()).then(|| classes.join("+")));
+
+        if enclosing.is_test() {
+            name.extend(enclosing.name().map(String::from));
+            let name = name.join(".");
+
+            // the cases of a parameterized test are named after its arguments,
+            // like `Class.Method(value: 0)`.
+            if takes_parameters(enclosing.content(), enclosing.name()?) {
+                Some(format!(
+                    "FullyQualifiedName={name}|FullyQualifiedName~{name}("
+                ))
+            } else {
+                Some(format!("FullyQualifiedName={name}"))
+            }
+        } else {
+            Some(format!("FullyQualifiedName~{}.", name.join(".")))
+        }
+    }
+
+    /// Finds the `--filter` for all of the top-level classes in the file, or
+    /// its namespace if it has none. Returns `None` if the whole project should
+    /// be run.
+    fn find_file_filter(&self, path: &TestFile) -> Option<String> {
+        let test_index = TestIndex::build(path, &self.config).ok()?;
+        let namespace = namespace(path);
+
+        let classes = test_index
+            .top_level()
+            .into_iter()
+            .filter(|entry| !entry.is_test())
+            .filter_map(|class| class.name())
+            .map(|class| match &namespace {
+                Some(namespace) => format!("FullyQualifiedName~{namespace}.{class}."),
+                None => format!("FullyQualifiedName~{class}."),
+            })
+            .collect::<Vec<_>>();
+
+        if classes.is_empty() {
+            namespace.map(|namespace| format!("FullyQualifiedName~{namespace}."))
+        } else {
+            Some(classes.join("|"))
+        }
+    }
+}
+
+impl Default for DotnetTest {
+    fn default() -> Self {
+        Self::new(Config::default().dotnet_test.clone())
+    }
+}
+
+impl TestAdapter for DotnetTest {
+    fn selector_matches(&self, selector: &TestSelector) -> SelectorMatch {
+        match selector {
+            PathWithLineNumber { path, .. } | PathOnly { path } => {
+                exclusive_or_none(path.matches(&self.config.file_patterns))
+            }
+            NameOnly { .. } => {
+                shared_or_none(!project_files(&current_dir().unwrap_or_default()).is_empty())
+            }
+        }
+    }
+
+    fn collect_commands(&self, selectors: &[&TestSelector]) -> Option<Vec<Cow<'_, str>>> {
+        let mut commands: Vec<Cow<str>> = vec![];
+
+        for selector in selectors {
+            let (project, filter) = match selector {
+                PathWithLineNumber { path, line } => {
+                    (project_of(path), self.find_filter(path, *line))
+                }
+                PathOnly { path } => (project_of(path), self.find_file_filter(path)),
+                NameOnly { name } => (None, Some(format!("FullyQualifiedName~{name}"))),
+            };
+
+            let mut args = vec!["dotnet".to_string(), "test".to_string()];
+
+            args.extend(project.map(|project| project.to_string_lossy().to_string()));
+            if let Some(filter) = filter {
+                args.extend(["--filter".to_string(), filter]);
+            }
+
+            commands.push(shell_words::join(args).into());
+        }
+
+        none_if_empty!(commands)
+    }
+}
+
+/// Finds the nearest project of the file, relative to the current directory.
+fn project_of(path: &TestFile) -> Option<PathBuf> {
+    let cwd = current_dir().ok()?;
+    let file = cwd.join(path);
+
+    let project = file.ancestors().skip(1).find_map(find_project)?;
+
+    Some(
+        project
+            .strip_prefix(&cwd)
+            .map(Path::to_path_buf)
+            .unwrap_or(project),
+    )
+}
+
+/// Finds a project file in the given directory, skipping solutions which may
+/// hold other projects than the one of the file.
+fn find_project(dir: &Path) -> Option<PathBuf> {
+    project_files(dir)
+        .into_iter()
+        .find(|project| project.extension() != Some("sln".as_ref()))
+}
+
+/// Lists the project and solution files in the given directory, sorted.
+fn project_files(dir: &Path) -> Vec<PathBuf> {
+    let mut projects = read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| PROJECT_EXTENSIONS.contains(&ext))
+        })
+        .collect::<Vec<_>>();
+
+    projects.sort();
+    projects
+}
+
+/// Returns true if the method declared on the given line takes parameters.
+fn takes_parameters(content: &str, name: &str) -> bool {
+    content
+        .split_once(name)
+        .and_then(|(_, signature)| signature.split_once('('))
+        .is_some_and(|(_, parameters)| !parameters.trim_start().starts_with(')'))
+}
+
+/// Reads the namespace a C# file, or the namespace or top-level module an F#
+/// file, declares.
+fn namespace(path: &TestFile) -> Option<String> {
+    let content = read_to_string(path).ok()?;
+
+    content.lines().find_map(|line| {
+        let line = line.trim();
+        let name = line
+            .strip_prefix("namespace ")
+            .or_else(|| line.strip_prefix("module ").filter(|m| !m.contains('=')))?;
+
+        Some(name.trim_end_matches([';', '{']).trim().to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_env;
+
+    const CSHARP_CONTENT: &str = r#"using Xunit;
+
+namespace Acme.Tests
+{
+    public class CalculatorTests
+    {
+        private int Helper() => 1;
+
+        [Fact]
+        public void AddsNumbers()
+        {
+            Assert.Equal(2, Helper() + 1);
+        }
+
+        public class WhenEmpty
+        {
+            [Theory]
+            [InlineData(0)]
+            public async Task IsEmpty(int value)
+            {
+                Assert.Equal(0, value);
+            }
+        }
+    }
+}
+"#;
+
+    const FSHARP_CONTENT: &str = r#"module Acme.Tests.CalculatorTests
+
+open Xunit
+
+[<Fact>]
+let ``adds numbers`` () =
+    Assert.Equal(2, 1 + 1)
+"#;
+
+    #[test]
+    fn selector_matches_test_files() {
+        test_env::with(|env| {
+            env.write_file("Acme.sln", "");
+            env.write_file("tests/Acme.Tests/CalculatorTests.cs", CSHARP_CONTENT);
+            env.write_file("src/Acme/Calculator.cs", "");
+            let adapter = DotnetTest::default();
+
+            assert_eq!(
+                SelectorMatch::Exclusive,
+                adapter.selector_matches(&env.selector("tests/Acme.Tests/CalculatorTests.cs:12"))
+            );
+            assert_eq!(
+                SelectorMatch::None,
+                adapter.selector_matches(&env.selector("src/Acme/Calculator.cs"))
+            );
+            assert_eq!(
+                SelectorMatch::Shared,
+                adapter.selector_matches(&env.selector("AddsNumbers"))
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_path_with_line_number() {
+        test_env::with(|env| {
+            env.write_file("tests/Acme.Tests/Acme.Tests.csproj", "");
+            env.write_file("tests/Acme.Tests/CalculatorTests.cs", CSHARP_CONTENT);
+
+            assert_eq!(
+                env.commands(&DotnetTest::default(), &["tests/Acme.Tests/CalculatorTests.cs:12"]),
+                vec!["dotnet test tests/Acme.Tests/Acme.Tests.csproj --filter 'FullyQualifiedName=Acme.Tests.CalculatorTests.AddsNumbers'"]
+            );
+            assert_eq!(
+                env.commands(&DotnetTest::default(), &["tests/Acme.Tests/CalculatorTests.cs:21"]),
+                vec!["dotnet test tests/Acme.Tests/Acme.Tests.csproj --filter 'FullyQualifiedName=Acme.Tests.CalculatorTests+WhenEmpty.IsEmpty|FullyQualifiedName~Acme.Tests.CalculatorTests+WhenEmpty.IsEmpty('"]
+            );
+            assert_eq!(
+                env.commands(&DotnetTest::default(), &["tests/Acme.Tests/CalculatorTests.cs:7"]),
+                vec!["dotnet test tests/Acme.Tests/Acme.Tests.csproj --filter 'FullyQualifiedName~Acme.Tests.CalculatorTests.'"]
+            );
+            assert_eq!(
+                env.commands(&DotnetTest::default(), &["tests/Acme.Tests/CalculatorTests.cs:1"]),
+                vec!["dotnet test tests/Acme.Tests/Acme.Tests.csproj --filter 'FullyQualifiedName~Acme.Tests.CalculatorTests.'"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_for_nunit_test_cases() {
+        test_env::with(|env| {
+            env.write_file("tests/Acme.Tests/Acme.Tests.csproj", "");
+            env.write_file(
+                "tests/Acme.Tests/ParserTests.cs",
+                "namespace Acme.Tests;\n\npublic class ParserTests\n{\n    [TestCase(\"1\", 1)]\n    [TestCase(\"2\", 2)]\n    public void ParsesNumbers(string input, int expected)\n    {\n    }\n}\n",
+            );
+
+            assert_eq!(
+                env.commands(&DotnetTest::default(), &["tests/Acme.Tests/ParserTests.cs:8"]),
+                vec!["dotnet test tests/Acme.Tests/Acme.Tests.csproj --filter 'FullyQualifiedName=Acme.Tests.ParserTests.ParsesNumbers|FullyQualifiedName~Acme.Tests.ParserTests.ParsesNumbers('"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_skips_solutions_next_to_projects() {
+        test_env::with(|env| {
+            env.write_file("Acme.sln", "");
+            env.write_file("Tests.csproj", "");
+            env.write_file("CalculatorTests.cs", CSHARP_CONTENT);
+
+            assert_eq!(
+                env.commands(&DotnetTest::default(), &["CalculatorTests.cs:12"]),
+                vec!["dotnet test Tests.csproj --filter 'FullyQualifiedName=Acme.Tests.CalculatorTests.AddsNumbers'"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_for_fsharp() {
+        test_env::with(|env| {
+            env.write_file("tests/Acme.Tests/Acme.Tests.fsproj", "");
+            env.write_file("tests/Acme.Tests/CalculatorTests.fs", FSHARP_CONTENT);
+
+            assert_eq!(
+                env.commands(&DotnetTest::default(), &["tests/Acme.Tests/CalculatorTests.fs:7"]),
+                vec!["dotnet test tests/Acme.Tests/Acme.Tests.fsproj --filter 'FullyQualifiedName=Acme.Tests.CalculatorTests.adds numbers'"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_path_only_and_name_only() {
+        test_env::with(|env| {
+            env.write_file("tests/Acme.Tests/Acme.Tests.csproj", "");
+            env.write_file("tests/Acme.Tests/CalculatorTests.cs", CSHARP_CONTENT);
+            env.write_file("tests/Acme.Tests/CalculatorTests.fs", FSHARP_CONTENT);
+
+            assert_eq!(
+                env.commands(&DotnetTest::default(), &["tests/Acme.Tests/CalculatorTests.cs"]),
+                vec!["dotnet test tests/Acme.Tests/Acme.Tests.csproj --filter 'FullyQualifiedName~Acme.Tests.CalculatorTests.'"]
+            );
+            assert_eq!(
+                env.commands(&DotnetTest::default(), &["tests/Acme.Tests/CalculatorTests.fs"]),
+                vec!["dotnet test tests/Acme.Tests/Acme.Tests.csproj --filter 'FullyQualifiedName~Acme.Tests.CalculatorTests.'"]
+            );
+            assert_eq!(
+                env.commands(&DotnetTest::default(), &["AddsNumbers"]),
+                vec!["dotnet test --filter 'FullyQualifiedName~AddsNumbers'"]
+            );
+        })
+    }
+}
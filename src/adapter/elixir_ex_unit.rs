@@ -0,0 +1,333 @@
+use super::{SelectorMatch, TestAdapter};
+use crate::{
+    config::{AdapterConfig, Config},
+    none_if_empty,
+    selector_match::{exclusive_or_none, shared_or_none},
+    test_file::TestFile,
+    test_index::TestIndex,
+    utils::file_exists,
+    TestSelector,
+};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::{
+    borrow::Cow,
+    fs::{read_dir, read_to_string},
+    path::Path,
+};
+use TestSelector::*;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ElixirExUnit {
+    config: AdapterConfig,
+}
+
+impl ElixirExUnit {
+    /// Creates a new ElixirExUnit adapter.
+    pub const fn new(config: AdapterConfig) -> Self {
+        Self { config }
+    }
+
+    /// Finds the full names of the tests with the given name in the project.
+    /// ExUnit names a test inside a `describe` after both, like `test parse
+    /// adds numbers`, so the name of the test alone doesn't select it.
+    fn find_test_names(&self, name: &str) -> Vec<String> {
+        let mut names = vec![];
+
+        for path in find_files(Path::new("."), &self.config.file_patterns) {
+            let test_index = match TestIndex::build(&path, &self.config) {
+                Ok(test_index) => test_index,
+                Err(_) => continue,
+            };
+
+            for test in test_index.tests() {
+                if test.name() != Some(name) {
+                    continue;
+                }
+
+                let mut full_name = vec!["test"];
+                full_name.extend(
+                    test_index
+                        .ancestors(test)
+                        .iter()
+                        .filter_map(|describe| describe.name()),
+                );
+                full_name.push(name);
+
+                let full_name = full_name.join(" ");
+                if !names.contains(&full_name) {
+                    names.push(full_name);
+                }
+            }
+        }
+
+        if names.is_empty() {
+            names.push(format!("test {name}"));
+        }
+        names
+    }
+}
+
+impl Default for ElixirExUnit {
+    fn default() -> Self {
+        Self::new(Config::default().elixir_ex_unit.clone())
+    }
+}
+
+impl TestAdapter for ElixirExUnit {
+    fn selector_matches(&self, selector: &TestSelector) -> SelectorMatch {
+        match selector {
+            PathWithLineNumber { path, .. } | PathOnly { path } => {
+                exclusive_or_none(path.matches(&self.config.file_patterns))
+            }
+            NameOnly { .. } => shared_or_none(file_exists("mix.exs")),
+        }
+    }
+
+    fn collect_commands(&self, selectors: &[&TestSelector]) -> Option<Vec<Cow<'_, str>>> {
+        // The args for each umbrella child app, or `None` for the project in
+        // the current directory, in the order they were first selected.
+        let mut batches: Vec<(Option<String>, Vec<String>)> = vec![];
+        let mut commands: Vec<Cow<str>> = vec![];
+
+        for selector in selectors {
+            let (app, arg) = match selector {
+                PathWithLineNumber { path, line } => {
+                    let (app, path) = umbrella_app(path);
+                    (app, format!("{path}:{line}"))
+                }
+                PathOnly { path } => umbrella_app(path),
+                NameOnly { name } => {
+                    let mut args = vec!["mix".to_string(), "test".to_string()];
+
+                    for test_name in self.find_test_names(name) {
+                        args.extend(["--only".to_string(), format!("test:{test_name}")]);
+                    }
+                    args.extend(["--only".to_string(), format!("describe:{name}")]);
+
+                    commands.push(shell_words::join(args).into());
+                    continue;
+                }
+            };
+
+            match batches.iter_mut().find(|(batch_app, _)| *batch_app == app) {
+                Some((_, args)) => args.push(arg),
+                None => batches.push((app, vec![arg])),
+            }
+        }
+
+        for (app, args) in batches {
+            let mut command = vec![];
+
+            if let Some(app) = app {
+                command.extend(["mix".to_string(), "cmd".to_string()]);
+                command.extend(["--app".to_string(), app]);
+            }
+            command.extend(["mix".to_string(), "test".to_string()]);
+            command.extend(args);
+
+            commands.push(shell_words::join(command).into());
+        }
+
+        none_if_empty!(commands)
+    }
+}
+
+/// Finds the files matching the patterns in the given directory and the ones
+/// below it, skipping hidden, build and dependency directories.
+fn find_files(dir: &Path, patterns: &[String]) -> Vec<TestFile> {
+    let mut paths = read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    let mut files = vec![];
+
+    for path in paths {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+
+        if path.is_dir() {
+            if !file_name.starts_with('.') && file_name != "_build" && file_name != "deps" {
+                files.extend(find_files(&path, patterns));
+            }
+        } else {
+            let file = TestFile::from(path);
+
+            if file.matches(patterns) {
+                files.push(file);
+            }
+        }
+    }
+
+    files
+}
+
+/// Finds the umbrella child app the file belongs to, if the project in the
+/// current directory is an umbrella, and the path of the file within it.
+fn umbrella_app(path: &TestFile) -> (Option<String>, String) {
+    lazy_static! {
+        static ref APPS_PATH: Regex = Regex::new(r#"apps_path:\s*"(?P<path>[^"]+)""#).unwrap();
+        static ref APP_NAME: Regex = Regex::new(r"\bapp:\s*:(?P<name>\w+)").unwrap();
+    }
+
+    let path_string = path.to_string_lossy().to_string();
+    let apps_path = read_to_string("mix.exs").ok().and_then(|mix| {
+        let captures = APPS_PATH.captures(&mix)?;
+        Some(captures["path"].trim_end_matches('/').to_string())
+    });
+
+    let relative = match apps_path
+        .as_ref()
+        .and_then(|apps_path| Path::new(path.as_ref()).strip_prefix(apps_path).ok())
+    {
+        Some(relative) => relative,
+        None => return (None, path_string),
+    };
+
+    let mut components = relative.components();
+    let dir = match components.next() {
+        Some(dir) => dir.as_os_str().to_string_lossy().to_string(),
+        None => return (None, path_string),
+    };
+    let app_root = Path::new(apps_path.as_deref().unwrap_or_default()).join(&dir);
+
+    // The app is named in its mix.exs, which conventionally matches its
+    // directory.
+    let app = read_to_string(app_root.join("mix.exs"))
+        .ok()
+        .and_then(|mix| Some(APP_NAME.captures(&mix)?["name"].to_string()))
+        .unwrap_or(dir);
+
+    (
+        Some(app),
+        components.as_path().to_string_lossy().to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_env;
+
+    const UMBRELLA_MIX: &str = r#"defmodule Acme.Umbrella.MixProject do
+  use Mix.Project
+
+  def project do
+    [apps_path: "apps", version: "0.1.0"]
+  end
+end
+"#;
+
+    #[test]
+    fn selector_matches_test_files() {
+        test_env::with(|env| {
+            env.write_file("mix.exs", "");
+            env.write_file("test/foo_test.exs", "");
+            env.write_file("lib/foo.ex", "");
+            let adapter = ElixirExUnit::default();
+
+            assert_eq!(
+                SelectorMatch::Exclusive,
+                adapter.selector_matches(&env.selector("test/foo_test.exs:23"))
+            );
+            assert_eq!(
+                SelectorMatch::None,
+                adapter.selector_matches(&env.selector("lib/foo.ex"))
+            );
+            assert_eq!(
+                SelectorMatch::Shared,
+                adapter.selector_matches(&env.selector("adds numbers"))
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_batches_paths() {
+        test_env::with(|env| {
+            env.write_file("mix.exs", "");
+            env.write_file("test/foo_test.exs", "");
+            env.write_file("test/bar_test.exs", "");
+
+            assert_eq!(
+                env.commands(
+                    &ElixirExUnit::default(),
+                    &["test/foo_test.exs:23", "test/bar_test.exs"]
+                ),
+                vec!["mix test test/foo_test.exs:23 test/bar_test.exs"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_name_only() {
+        test_env::with(|env| {
+            env.write_file("mix.exs", "");
+
+            assert_eq!(
+                env.commands(&ElixirExUnit::default(), &["adds numbers"]),
+                vec!["mix test --only 'test:test adds numbers' --only 'describe:adds numbers'"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_name_only_in_describe() {
+        test_env::with(|env| {
+            env.write_file("mix.exs", "");
+            env.write_file(
+                "test/calculator_test.exs",
+                r#"defmodule CalculatorTest do
+  use ExUnit.Case
+
+  describe "add/2" do
+    test "adds numbers" do
+      assert Calculator.add(1, 1) == 2
+    end
+  end
+
+  test "adds numbers" do
+    assert Calculator.add(1, 2) == 3
+  end
+end
+"#,
+            );
+            env.write_file(
+                "deps/calc/test/calc_test.exs",
+                "describe \"vendored\" do\n  test \"adds numbers\" do\n  end\nend\n",
+            );
+
+            assert_eq!(
+                env.commands(&ElixirExUnit::default(), &["adds numbers"]),
+                vec!["mix test --only 'test:test add/2 adds numbers' --only 'test:test adds numbers' --only 'describe:adds numbers'"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_runs_in_umbrella_apps() {
+        test_env::with(|env| {
+            env.write_file("mix.exs", UMBRELLA_MIX);
+            env.write_file("apps/web/mix.exs", "[app: :acme_web, version: \"0.1.0\"]");
+            env.write_file("apps/web/test/page_test.exs", "");
+            env.write_file("apps/core/test/user_test.exs", "");
+            env.write_file("apps/core/test/post_test.exs", "");
+
+            assert_eq!(
+                env.commands(
+                    &ElixirExUnit::default(),
+                    &[
+                        "apps/web/test/page_test.exs:5",
+                        "apps/core/test/user_test.exs:12",
+                        "apps/core/test/post_test.exs",
+                    ]
+                ),
+                vec![
+                    "mix cmd --app acme_web mix test test/page_test.exs:5",
+                    "mix cmd --app core mix test test/user_test.exs:12 test/post_test.exs",
+                ]
+            );
+        })
+    }
+}
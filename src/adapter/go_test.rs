@@ -0,0 +1,262 @@
+use super::{SelectorMatch, TestAdapter};
+use crate::{
+    config::{AdapterConfig, Config},
+    none_if_empty,
+    selector_match::{exclusive_or_none, shared_or_none},
+    test_file::TestFile,
+    test_index::TestIndex,
+    utils::{file_exists, find_up},
+    TestSelector,
+};
+use std::{
+    borrow::Cow,
+    env::current_dir,
+    path::{Path, PathBuf},
+};
+use TestSelector::*;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct GoTest {
+    config: AdapterConfig,
+}
+
+impl GoTest {
+    /// Creates a new GoTest adapter.
+    pub const fn new(config: AdapterConfig) -> Self {
+        Self { config }
+    }
+
+    /// Finds the `-run` pattern for the test function and subtests enclosing
+    /// the given line. Returns `None` if the whole package should be run.
+    fn find_run_pattern(&self, path: &TestFile, line: u32) -> Option<String> {
+        let test_index = TestIndex::build(path, &self.config).ok()?;
+        let enclosing = test_index.enclosing(line)?;
+
+        let ancestors = test_index.ancestors(enclosing);
+        let segments = ancestors
+            .iter()
+            .chain([&enclosing])
+            .filter_map(|entry| entry.name())
+            .map(|name| format!("^{}$", regex::escape(&name.replace(' ', "_"))))
+            .collect::<Vec<_>>();
+
+        none_if_empty!(segments).map(|segments| segments.join("/"))
+    }
+}
+
+impl Default for GoTest {
+    fn default() -> Self {
+        Self::new(Config::default().go_test.clone())
+    }
+}
+
+impl TestAdapter for GoTest {
+    fn selector_matches(&self, selector: &TestSelector) -> SelectorMatch {
+        match selector {
+            PathWithLineNumber { path, .. } | PathOnly { path } => {
+                exclusive_or_none(path.matches(&self.config.file_patterns))
+            }
+            NameOnly { .. } => shared_or_none(file_exists("go.mod")),
+        }
+    }
+
+    fn collect_commands(&self, selectors: &[&TestSelector]) -> Option<Vec<Cow<'_, str>>> {
+        let mut commands: Vec<Cow<str>> = vec![];
+
+        for selector in selectors {
+            match selector {
+                PathWithLineNumber { path, line } => {
+                    let package = GoPackage::locate(path);
+                    let pattern = self.find_run_pattern(path, *line);
+
+                    commands.push(package.command(pattern.as_deref()).into());
+                }
+                PathOnly { path } => commands.push(GoPackage::locate(path).command(None).into()),
+                NameOnly { name } => {
+                    let package = GoPackage {
+                        module_root: None,
+                        package: "./...".into(),
+                    };
+
+                    commands.push(package.command(Some(name)).into());
+                }
+            };
+        }
+
+        none_if_empty!(commands)
+    }
+}
+
+/// A Go package, and the module it belongs to.
+#[derive(Debug, PartialEq, Eq)]
+struct GoPackage {
+    /// The directory of the nearest go.mod, relative to the current directory,
+    /// if it is not the current directory.
+    module_root: Option<PathBuf>,
+    /// The package path, relative to the module root.
+    package: String,
+}
+
+impl GoPackage {
+    /// Locates the package of a test file, using the nearest go.mod.
+    fn locate(path: &TestFile) -> Self {
+        let cwd = current_dir().unwrap_or_default();
+        let dir = cwd.join(path);
+        let dir = dir.parent().unwrap_or(&cwd);
+
+        let root = find_up(dir, "go.mod")
+            .and_then(|go_mod| go_mod.parent().map(Path::to_path_buf))
+            .unwrap_or_else(|| cwd.clone());
+        let package = match dir.strip_prefix(&root) {
+            Ok(relative) if relative.as_os_str().is_empty() => ".".to_string(),
+            Ok(relative) => format!("./{}", relative.display()),
+            Err(_) => ".".to_string(),
+        };
+        let module_root = match root.strip_prefix(&cwd) {
+            Ok(relative) if relative.as_os_str().is_empty() => None,
+            Ok(relative) => Some(relative.to_path_buf()),
+            Err(_) => Some(root),
+        };
+
+        Self {
+            module_root,
+            package,
+        }
+    }
+
+    /// Builds the `go test` command for the package, running it from the
+    /// module root.
+    fn command(&self, run_pattern: Option<&str>) -> String {
+        let mut args = vec!["go".to_string()];
+
+        if let Some(module_root) = &self.module_root {
+            args.extend(["-C".to_string(), module_root.to_string_lossy().into()]);
+        }
+        args.extend(["test".to_string(), self.package.to_string()]);
+        if let Some(run_pattern) = run_pattern {
+            args.extend(["-run".to_string(), run_pattern.to_string()]);
+        }
+
+        shell_words::join(args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_env;
+
+    const TEST_CONTENT: &str = r#"package bar
+
+import "testing"
+
+func helper() int {
+	return 1
+}
+
+func TestAdd(t *testing.T) {
+	t.Run("small numbers", func(t *testing.T) {
+		if helper() != 1 {
+			t.Fail()
+		}
+	})
+
+	t.Run("large numbers", func(t *testing.T) {
+		t.Run("overflow", func(t *testing.T) {
+			t.Skip()
+		})
+	})
+}
+"#;
+
+    #[test]
+    fn selector_matches_test_files() {
+        test_env::with(|env| {
+            env.write_file("go.mod", "module example.com/foo\n");
+            env.write_file("pkg/foo/bar_test.go", TEST_CONTENT);
+            env.write_file("pkg/foo/bar.go", "package bar\n");
+            let adapter = GoTest::default();
+
+            assert_eq!(
+                SelectorMatch::Exclusive,
+                adapter.selector_matches(&env.selector("pkg/foo/bar_test.go:10"))
+            );
+            assert_eq!(
+                SelectorMatch::None,
+                adapter.selector_matches(&env.selector("pkg/foo/bar.go"))
+            );
+            assert_eq!(
+                SelectorMatch::Shared,
+                adapter.selector_matches(&env.selector("TestAdd"))
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_path_with_line_number() {
+        test_env::with(|env| {
+            env.write_file("go.mod", "module example.com/foo\n");
+            env.write_file("pkg/foo/bar_test.go", TEST_CONTENT);
+
+            assert_eq!(
+                env.commands(&GoTest::default(), &["pkg/foo/bar_test.go:15"]),
+                vec!["go test ./pkg/foo -run '^TestAdd$'"]
+            );
+            assert_eq!(
+                env.commands(&GoTest::default(), &["pkg/foo/bar_test.go:11"]),
+                vec!["go test ./pkg/foo -run '^TestAdd$/^small_numbers$'"]
+            );
+            assert_eq!(
+                env.commands(&GoTest::default(), &["pkg/foo/bar_test.go:18"]),
+                vec!["go test ./pkg/foo -run '^TestAdd$/^large_numbers$/^overflow$'"]
+            );
+            assert_eq!(
+                env.commands(&GoTest::default(), &["pkg/foo/bar_test.go:5"]),
+                vec!["go test ./pkg/foo"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_path_only() {
+        test_env::with(|env| {
+            env.write_file("go.mod", "module example.com/foo\n");
+            env.write_file("pkg/foo/bar_test.go", TEST_CONTENT);
+            env.write_file("root_test.go", TEST_CONTENT);
+
+            assert_eq!(
+                env.commands(&GoTest::default(), &["pkg/foo/bar_test.go"]),
+                vec!["go test ./pkg/foo"]
+            );
+            assert_eq!(
+                env.commands(&GoTest::default(), &["root_test.go"]),
+                vec!["go test ."]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_name_only() {
+        test_env::with(|env| {
+            env.write_file("go.mod", "module example.com/foo\n");
+
+            assert_eq!(
+                env.commands(&GoTest::default(), &["TestAdd"]),
+                vec!["go test ./... -run TestAdd"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_runs_from_the_module_root() {
+        test_env::with(|env| {
+            env.write_file("services/api/go.mod", "module example.com/api\n");
+            env.write_file("services/api/pkg/foo/bar_test.go", TEST_CONTENT);
+
+            assert_eq!(
+                env.commands(&GoTest::default(), &["services/api/pkg/foo/bar_test.go:11"]),
+                vec!["go -C services/api test ./pkg/foo -run '^TestAdd$/^small_numbers$'"]
+            );
+        })
+    }
+}
@@ -0,0 +1,395 @@
+use super::{SelectorMatch, TestAdapter};
+use crate::{
+    config::{AdapterConfig, Config},
+    none_if_empty,
+    selector_match::{exclusive_or_none, shared_or_none},
+    test_file::TestFile,
+    test_index::TestIndex,
+    utils::file_exists,
+    TestSelector,
+};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::{
+    borrow::Cow,
+    env::current_dir,
+    fs::{read_dir, read_to_string},
+    path::{Path, PathBuf},
+};
+use TestSelector::*;
+
+/// The framework a test file is written for, which decides how its tests are
+/// selected.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Framework {
+    Hspec,
+    Tasty,
+}
+
+impl Framework {
+    /// Detects the framework from the modules a test file imports.
+    fn of(path: &TestFile) -> Self {
+        match read_to_string(path) {
+            Ok(content) if content.contains("import Test.Tasty") => Self::Tasty,
+            _ => Self::Hspec,
+        }
+    }
+
+    /// Builds the test options which select the test or group at the end of
+    /// the given path of names.
+    fn path_option(self, names: &[&str], is_test: bool) -> String {
+        match self {
+            Self::Hspec => format!("--match \"/{}/\"", names.join("/")),
+            Self::Tasty => {
+                let names = names.iter().map(|name| escape_ere(name));
+                let pattern = names.collect::<Vec<_>>().join(r"\.");
+
+                if is_test {
+                    format!("-p \"/^{pattern}$/\"")
+                } else {
+                    format!("-p \"/^{pattern}\\./\"")
+                }
+            }
+        }
+    }
+
+    /// Builds the test options which select the tests whose names contain the
+    /// given text.
+    fn name_option(self, name: &str) -> String {
+        match self {
+            Self::Hspec => format!("--match \"{name}\""),
+            Self::Tasty => format!("-p \"/{}/\"", escape_ere(name)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct HaskellTest {
+    config: AdapterConfig,
+}
+
+impl HaskellTest {
+    /// Creates a new HaskellTest adapter.
+    pub const fn new(config: AdapterConfig) -> Self {
+        Self { config }
+    }
+
+    /// Finds the test options selecting the `it` or `describe` (or tasty
+    /// `testCase` or `testGroup`) enclosing the given line, or all of the
+    /// top-level ones in the file. Returns `None` if the whole suite should
+    /// be run.
+    fn find_test_options(&self, path: &TestFile, line: Option<u32>) -> Option<String> {
+        let test_index = TestIndex::build(path, &self.config).ok()?;
+        let framework = Framework::of(path);
+
+        if let Some(enclosing) = line.and_then(|line| test_index.enclosing(line)) {
+            let names = test_index
+                .ancestors(enclosing)
+                .into_iter()
+                .chain([enclosing])
+                .filter_map(|entry| entry.name())
+                .collect::<Vec<_>>();
+
+            return Some(framework.path_option(&names, enclosing.is_test()));
+        }
+
+        let options = test_index
+            .top_level()
+            .into_iter()
+            .filter_map(|entry| Some(framework.path_option(&[entry.name()?], entry.is_test())))
+            .collect::<Vec<_>>();
+
+        match (framework, options.len()) {
+            (_, 0) => None,
+            // tasty only takes one pattern, so there is no way to select
+            // several groups with it.
+            (Framework::Tasty, 2..) => None,
+            _ => Some(options.join(" ")),
+        }
+    }
+}
+
+impl Default for HaskellTest {
+    fn default() -> Self {
+        Self::new(Config::default().haskell_test.clone())
+    }
+}
+
+impl TestAdapter for HaskellTest {
+    fn selector_matches(&self, selector: &TestSelector) -> SelectorMatch {
+        match selector {
+            PathWithLineNumber { path, .. } | PathOnly { path } => {
+                exclusive_or_none(uses_haskell() && path.matches(&self.config.file_patterns))
+            }
+            NameOnly { .. } => shared_or_none(uses_haskell()),
+        }
+    }
+
+    fn collect_commands(&self, selectors: &[&TestSelector]) -> Option<Vec<Cow<'_, str>>> {
+        let mut commands: Vec<Cow<str>> = vec![];
+
+        for selector in selectors {
+            let (target, options) = match selector {
+                PathWithLineNumber { path, line } => {
+                    (test_suite(path), self.find_test_options(path, Some(*line)))
+                }
+                PathOnly { path } => (test_suite(path), self.find_test_options(path, None)),
+                NameOnly { name } => (None, Some(project_framework().name_option(name))),
+            };
+
+            let mut args = if file_exists("stack.yaml") {
+                vec!["stack".to_string(), "test".to_string()]
+            } else {
+                vec!["cabal".to_string(), "test".to_string()]
+            };
+
+            args.extend(target);
+            if let Some(options) = options {
+                if args[0] == "stack" {
+                    args.extend(["--ta".to_string(), options]);
+                } else {
+                    args.push(format!("--test-options={options}"));
+                }
+            }
+
+            commands.push(shell_words::join(args).into());
+        }
+
+        none_if_empty!(commands)
+    }
+}
+
+/// Returns true if the current directory holds a stack or cabal project.
+fn uses_haskell() -> bool {
+    file_exists("stack.yaml")
+        || file_exists("cabal.project")
+        || cabal_file(&current_dir().unwrap_or_default()).is_some()
+}
+
+/// Detects the framework the package in the current directory tests with.
+fn project_framework() -> Framework {
+    let uses_tasty = cabal_file(&current_dir().unwrap_or_default())
+        .and_then(|cabal_file| read_to_string(cabal_file).ok())
+        .is_some_and(|cabal| cabal.contains("tasty"));
+
+    if uses_tasty {
+        Framework::Tasty
+    } else {
+        Framework::Hspec
+    }
+}
+
+/// Finds the `package:test:suite` target of the test suite whose source
+/// directory contains the file, from the cabal file of its package.
+fn test_suite(path: &TestFile) -> Option<String> {
+    lazy_static! {
+        static ref PACKAGE_NAME: Regex = Regex::new(r"(?im)^name:\s*(?P<name>\S+)").unwrap();
+        static ref TEST_SUITE: Regex = Regex::new(r"(?i)^test-suite\s+(?P<name>\S+)").unwrap();
+        static ref SOURCE_DIRS: Regex =
+            Regex::new(r"(?i)^\s+hs-source-dirs:\s*(?P<dirs>.*)$").unwrap();
+    }
+
+    let cwd = current_dir().ok()?;
+    let file = cwd.join(path);
+    let cabal_file = file
+        .ancestors()
+        .skip(1)
+        .take_while(|dir| dir.starts_with(&cwd))
+        .find_map(cabal_file)?;
+    let package_dir = cabal_file.parent()?;
+    let cabal = read_to_string(&cabal_file).ok()?;
+    let package = &PACKAGE_NAME.captures(&cabal)?["name"];
+
+    let mut suites: Vec<(String, PathBuf)> = vec![];
+    let mut current_suite = None;
+
+    for line in cabal.lines() {
+        if let Some(captures) = TEST_SUITE.captures(line) {
+            current_suite = Some(captures["name"].to_string());
+        } else if !line.starts_with(char::is_whitespace) && !line.trim().is_empty() {
+            current_suite = None;
+        } else if let (Some(suite), Some(captures)) = (&current_suite, SOURCE_DIRS.captures(line)) {
+            let dirs = captures["dirs"]
+                .split([',', ' '])
+                .filter(|dir| !dir.is_empty());
+            suites.extend(dirs.map(|dir| (suite.clone(), package_dir.join(dir))));
+        }
+    }
+
+    suites
+        .into_iter()
+        .filter(|(_, dir)| file.starts_with(dir))
+        .max_by_key(|(_, dir)| dir.components().count())
+        .map(|(suite, _)| format!("{package}:test:{suite}"))
+}
+
+/// Finds a cabal file in the given directory.
+fn cabal_file(dir: &Path) -> Option<PathBuf> {
+    read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .find(|path| path.extension().is_some_and(|ext| ext == "cabal"))
+}
+
+/// Escapes the characters with special meaning in the extended regular
+/// expressions tasty patterns are made of.
+fn escape_ere(text: &str) -> String {
+    text.chars()
+        .flat_map(|c| match c {
+            '\\' | '.' | '[' | ']' | '(' | ')' | '*' | '+' | '?' | '{' | '}' | '|' | '^' | '$'
+            | '/' => vec!['\\', c],
+            c => vec![c],
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_env;
+
+    const CABAL_FILE: &str = r#"cabal-version: 2.4
+name:          acme
+version:       0.1.0
+
+library
+  hs-source-dirs: src
+  exposed-modules: Acme.Parser
+
+test-suite acme-test
+  type:           exitcode-stdio-1.0
+  main-is:        Spec.hs
+  hs-source-dirs: test
+
+test-suite acme-props
+  type:           exitcode-stdio-1.0
+  main-is:        PropertyTests.hs
+  hs-source-dirs: tasty
+  build-depends:  tasty, tasty-hunit
+"#;
+
+    const HSPEC_CONTENT: &str = r#"module Acme.ParserSpec (spec) where
+
+import Test.Hspec
+
+spec :: Spec
+spec = do
+  describe "parse" $ do
+    it "parses numbers" $ do
+      parse "1" `shouldBe` Just 1
+
+    context "when empty" $
+      it "returns Nothing" $
+        parse "" `shouldBe` Nothing
+"#;
+
+    const TASTY_CONTENT: &str = r#"module Main (main) where
+
+import Test.Tasty
+import Test.Tasty.HUnit
+
+main :: IO ()
+main = defaultMain $ testGroup "Parser"
+  [ testCase "parses 1.0" $
+      parse "1.0" @?= Just 1
+  ]
+"#;
+
+    #[test]
+    fn selector_matches_in_haskell_projects() {
+        test_env::with(|env| {
+            env.write_file("test/Acme/ParserSpec.hs", HSPEC_CONTENT);
+            env.write_file("src/Acme/Parser.hs", "");
+            let adapter = HaskellTest::default();
+            let selector = env.selector("test/Acme/ParserSpec.hs:9");
+
+            assert_eq!(SelectorMatch::None, adapter.selector_matches(&selector));
+
+            env.write_file("acme.cabal", CABAL_FILE);
+
+            assert_eq!(
+                SelectorMatch::Exclusive,
+                adapter.selector_matches(&selector)
+            );
+            assert_eq!(
+                SelectorMatch::None,
+                adapter.selector_matches(&env.selector("src/Acme/Parser.hs"))
+            );
+            assert_eq!(
+                SelectorMatch::Shared,
+                adapter.selector_matches(&env.selector("parses numbers"))
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_for_hspec_with_cabal() {
+        test_env::with(|env| {
+            env.write_file("acme.cabal", CABAL_FILE);
+            env.write_file("test/Acme/ParserSpec.hs", HSPEC_CONTENT);
+
+            assert_eq!(
+                env.commands(&HaskellTest::default(), &["test/Acme/ParserSpec.hs:9"]),
+                vec![
+                    r#"cabal test acme:test:acme-test '--test-options=--match "/parse/parses numbers/"'"#
+                ]
+            );
+            assert_eq!(
+                env.commands(&HaskellTest::default(), &["test/Acme/ParserSpec.hs:13"]),
+                vec![
+                    r#"cabal test acme:test:acme-test '--test-options=--match "/parse/when empty/returns Nothing/"'"#
+                ]
+            );
+            assert_eq!(
+                env.commands(&HaskellTest::default(), &["test/Acme/ParserSpec.hs:11"]),
+                vec![
+                    r#"cabal test acme:test:acme-test '--test-options=--match "/parse/when empty/"'"#
+                ]
+            );
+            assert_eq!(
+                env.commands(&HaskellTest::default(), &["test/Acme/ParserSpec.hs"]),
+                vec![r#"cabal test acme:test:acme-test '--test-options=--match "/parse/"'"#]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_for_tasty_with_stack() {
+        test_env::with(|env| {
+            env.write_file("stack.yaml", "resolver: lts-22.0\n");
+            env.write_file("acme.cabal", CABAL_FILE);
+            env.write_file("tasty/PropertyTests.hs", TASTY_CONTENT);
+
+            assert_eq!(
+                env.commands(&HaskellTest::default(), &["tasty/PropertyTests.hs:9"]),
+                vec![r#"stack test acme:test:acme-props --ta '-p "/^Parser\.parses 1\.0$/"'"#]
+            );
+            assert_eq!(
+                env.commands(&HaskellTest::default(), &["tasty/PropertyTests.hs:4"]),
+                vec![r#"stack test acme:test:acme-props --ta '-p "/^Parser\./"'"#]
+            );
+            assert_eq!(
+                env.commands(&HaskellTest::default(), &["parses"]),
+                vec![r#"stack test --ta '-p "/parses/"'"#]
+            );
+        })
+    }
+
+    #[test]
+    fn test_suite_in_a_subpackage() {
+        test_env::with(|env| {
+            env.write_file("cabal.project", "packages: acme\n");
+            env.write_file("acme/acme.cabal", CABAL_FILE);
+            env.write_file("acme/test/Acme/ParserSpec.hs", HSPEC_CONTENT);
+
+            assert_eq!(
+                test_suite(&"acme/test/Acme/ParserSpec.hs".into()),
+                Some("acme:test:acme-test".to_string())
+            );
+            assert_eq!(
+                env.commands(&HaskellTest::default(), &["parses numbers"]),
+                vec![r#"cabal test '--test-options=--match "parses numbers"'"#]
+            );
+        })
+    }
+}
@@ -0,0 +1,246 @@
+use super::{
+    bun_test::uses_bun, deno_test::uses_deno, js_vitest::uses_vitest, SelectorMatch, TestAdapter,
+};
+use crate::{
+    config::{AdapterConfig, Config},
+    memoize_string, none_if_empty,
+    selector_match::{exclusive_or_none, shared_or_none},
+    test_file::TestFile,
+    test_index::TestIndex,
+    utils::{file_exists, is_in_file},
+    TestSelector,
+};
+use std::borrow::Cow;
+use TestSelector::*;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct JsJest {
+    config: AdapterConfig,
+}
+
+impl JsJest {
+    /// Creates a new JsJest adapter.
+    pub const fn new(config: AdapterConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Default for JsJest {
+    fn default() -> Self {
+        Self::new(Config::default().js_jest.clone())
+    }
+}
+
+impl TestAdapter for JsJest {
+    fn selector_matches(&self, selector: &TestSelector) -> SelectorMatch {
+        if uses_bun() || uses_deno() {
+            return SelectorMatch::None;
+        }
+
+        match selector {
+            PathWithLineNumber { path, .. } | PathOnly { path } => {
+                exclusive_or_none(path.matches(&self.config.file_patterns))
+            }
+            NameOnly { .. } => shared_or_none(is_in_file("package.json", "jest") && !uses_vitest()),
+        }
+    }
+
+    fn collect_commands(&self, selectors: &[&TestSelector]) -> Option<Vec<Cow<'_, str>>> {
+        let mut commands: Vec<Cow<str>> = vec![];
+
+        for selector in selectors {
+            let mut args = vec![];
+
+            match selector {
+                PathWithLineNumber { path, line } => {
+                    args.push(path.to_string_lossy().to_string());
+
+                    if let Some(pattern) = find_name_pattern(&self.config, path, *line) {
+                        args.extend(["--testNamePattern".to_string(), pattern]);
+                    }
+                }
+                PathOnly { path } => args.push(path.to_string_lossy().to_string()),
+                NameOnly { name } => {
+                    args.extend(["--testNamePattern".to_string(), name.to_string()])
+                }
+            };
+
+            commands.push(format!("{} {}", jest_command(), shell_words::join(args)).into());
+        }
+
+        none_if_empty!(commands)
+    }
+}
+
+/// Finds a pattern matching the full name of the `it` or `describe` block
+/// enclosing the given line, which is the titles of it and each `describe` it
+/// is nested in, joined by spaces. Returns `None` if the whole file should be
+/// run.
+pub(super) fn find_name_pattern(
+    config: &AdapterConfig,
+    path: &TestFile,
+    line: u32,
+) -> Option<String> {
+    let test_index = TestIndex::build(path, config).ok()?;
+    let enclosing = test_index.enclosing(line)?;
+
+    let ancestors = test_index.ancestors(enclosing);
+    let title = ancestors
+        .iter()
+        .chain([&enclosing])
+        .filter_map(|entry| entry.name())
+        .map(regex::escape)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if enclosing.is_test() {
+        Some(format!("^{title}$"))
+    } else {
+        Some(format!("^{title} "))
+    }
+}
+
+/// Returns the command to run Jest. Projects without a lockfile run it through
+/// their package.json `test` script, if they have one.
+fn jest_command() -> Cow<'static, str> {
+    memoize_string!({
+        let has_lockfile = ["yarn.lock", "pnpm-lock.yaml", "package-lock.json"]
+            .into_iter()
+            .any(file_exists);
+
+        if !has_lockfile && is_in_file("package.json", "\"test\":") {
+            "npm test --".to_string()
+        } else {
+            js_package_command("jest")
+        }
+    })
+}
+
+/// Returns the command to run a binary from the project's node_modules, based
+/// on the lockfile of its package manager.
+pub(super) fn js_package_command(bin: &str) -> String {
+    if file_exists("yarn.lock") {
+        format!("yarn {bin}")
+    } else if file_exists("pnpm-lock.yaml") {
+        format!("pnpm {bin}")
+    } else {
+        format!("npx {bin}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_env;
+
+    const TEST_CONTENT: &str = r#"import { add } from "../add";
+
+const helper = () => 1;
+
+describe("add", () => {
+  it("adds numbers", () => {
+    expect(add(1, 2)).toBe(3);
+  });
+
+  describe('with negatives (edge cases)', () => {
+    test.only(`subtracts`, () => {
+      expect(add(1, -2)).toBe(-1);
+    });
+  });
+});
+"#;
+
+    #[test]
+    fn selector_matches_test_files() {
+        test_env::with(|env| {
+            env.write_file(
+                "package.json",
+                r#"{ "devDependencies": { "jest": "^29" } }"#,
+            );
+            env.write_file("src/add.test.ts", TEST_CONTENT);
+            env.write_file("src/__tests__/add.js", TEST_CONTENT);
+            env.write_file("src/add.ts", "");
+            let adapter = JsJest::default();
+
+            assert_eq!(
+                SelectorMatch::Exclusive,
+                adapter.selector_matches(&env.selector("src/add.test.ts:7"))
+            );
+            assert_eq!(
+                SelectorMatch::Exclusive,
+                adapter.selector_matches(&env.selector("src/__tests__/add.js"))
+            );
+            assert_eq!(
+                SelectorMatch::None,
+                adapter.selector_matches(&env.selector("src/add.ts"))
+            );
+            assert_eq!(
+                SelectorMatch::Shared,
+                adapter.selector_matches(&env.selector("adds numbers"))
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_path_with_line_number() {
+        test_env::with(|env| {
+            env.write_file("src/add.test.ts", TEST_CONTENT);
+
+            assert_eq!(
+                env.commands(&JsJest::default(), &["src/add.test.ts:7"]),
+                vec!["npx jest src/add.test.ts --testNamePattern '^add adds numbers$'"]
+            );
+            assert_eq!(
+                env.commands(&JsJest::default(), &["src/add.test.ts:12"]),
+                vec![
+                    r"npx jest src/add.test.ts --testNamePattern '^add with negatives \(edge cases\) subtracts$'"
+                ]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_path_with_line_number_outside_of_tests() {
+        test_env::with(|env| {
+            env.write_file("src/add.test.ts", TEST_CONTENT);
+
+            assert_eq!(
+                env.commands(&JsJest::default(), &["src/add.test.ts:9"]),
+                vec!["npx jest src/add.test.ts --testNamePattern '^add '"]
+            );
+            assert_eq!(
+                env.commands(&JsJest::default(), &["src/add.test.ts:3"]),
+                vec!["npx jest src/add.test.ts"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_name_only() {
+        test_env::with(|env| {
+            assert_eq!(
+                env.commands(&JsJest::default(), &["adds numbers"]),
+                vec!["npx jest --testNamePattern 'adds numbers'"]
+            );
+        })
+    }
+
+    #[test]
+    fn jest_command_detects_package_managers() {
+        for (lockfile, expected) in [
+            ("yarn.lock", "yarn jest"),
+            ("pnpm-lock.yaml", "pnpm jest"),
+            ("package-lock.json", "npx jest"),
+        ] {
+            test_env::with(|env| {
+                env.write_file(lockfile, "");
+                assert_eq!(expected, jest_command());
+            })
+        }
+
+        test_env::with(|env| {
+            env.write_file("package.json", r#"{ "scripts": { "test": "jest" } }"#);
+            assert_eq!("npm test --", jest_command());
+        })
+    }
+}
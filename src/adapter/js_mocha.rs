@@ -0,0 +1,398 @@
+use super::{
+    bun_test::uses_bun,
+    deno_test::uses_deno,
+    js_jest::{find_name_pattern, js_package_command},
+    SelectorMatch, TestAdapter,
+};
+use crate::{
+    config::{AdapterConfig, Config},
+    memoize_string, none_if_empty,
+    selector_match::{exclusive_or_none, shared_or_none},
+    utils::{file_exists, is_in_file},
+    TestSelector,
+};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::{borrow::Cow, env::current_dir, fs::read_to_string};
+use TestSelector::*;
+
+/// The files Mocha reads its configuration from, in order of precedence.
+const MOCHARC_FILES: [&str; 6] = [
+    ".mocharc.js",
+    ".mocharc.cjs",
+    ".mocharc.yaml",
+    ".mocharc.yml",
+    ".mocharc.jsonc",
+    ".mocharc.json",
+];
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct JsMocha {
+    config: AdapterConfig,
+}
+
+impl JsMocha {
+    /// Creates a new JsMocha adapter.
+    pub const fn new(config: AdapterConfig) -> Self {
+        Self { config }
+    }
+
+    /// Returns the patterns of the spec files, from the Mocha configuration if
+    /// it sets any, falling back to the configured file patterns.
+    fn spec_patterns(&self) -> Vec<String> {
+        let globs = mocha_spec_globs();
+
+        if globs.is_empty() {
+            self.config.file_patterns.clone()
+        } else {
+            globs.iter().map(|glob| glob_to_regex(glob)).collect()
+        }
+    }
+}
+
+impl Default for JsMocha {
+    fn default() -> Self {
+        Self::new(Config::default().js_mocha.clone())
+    }
+}
+
+impl TestAdapter for JsMocha {
+    fn selector_matches(&self, selector: &TestSelector) -> SelectorMatch {
+        if uses_bun() || uses_deno() {
+            return SelectorMatch::None;
+        }
+
+        match selector {
+            PathWithLineNumber { path, .. } | PathOnly { path } => {
+                exclusive_or_none(uses_mocha() && path.matches(&self.spec_patterns()))
+            }
+            NameOnly { .. } => shared_or_none(uses_mocha()),
+        }
+    }
+
+    fn collect_commands(&self, selectors: &[&TestSelector]) -> Option<Vec<Cow<'_, str>>> {
+        let mut commands: Vec<Cow<str>> = vec![];
+
+        for selector in selectors {
+            let mut args = vec![];
+
+            match selector {
+                PathWithLineNumber { path, line } => {
+                    args.push(path.to_string_lossy().to_string());
+
+                    if let Some(pattern) = find_name_pattern(&self.config, path, *line) {
+                        args.extend(["--grep".to_string(), pattern]);
+                    }
+                }
+                PathOnly { path } => args.push(path.to_string_lossy().to_string()),
+                NameOnly { name } => args.extend(["--grep".to_string(), name.to_string()]),
+            };
+
+            commands.push(format!("{} {}", mocha_command(), shell_words::join(args)).into());
+        }
+
+        none_if_empty!(commands)
+    }
+}
+
+/// Returns true if the project has a Mocha configuration.
+fn uses_mocha() -> bool {
+    MOCHARC_FILES.into_iter().any(file_exists) || is_in_file("package.json", "\"mocha\"")
+}
+
+fn mocha_command() -> Cow<'static, str> {
+    memoize_string!(js_package_command("mocha"))
+}
+
+/// Reads the `spec` globs from the Mocha configuration file, or from the
+/// `mocha` key of the package.json.
+fn mocha_spec_globs() -> Vec<String> {
+    let cwd = current_dir().unwrap_or_default();
+
+    let config = MOCHARC_FILES
+        .iter()
+        .find_map(|file| read_to_string(cwd.join(file)).ok())
+        .or_else(|| {
+            let package = read_to_string(cwd.join("package.json")).ok()?;
+
+            mocha_object(&package).map(String::from)
+        });
+
+    config.map(|config| spec_globs(&config)).unwrap_or_default()
+}
+
+/// Finds the body of the object under the `mocha` key of a package.json,
+/// which may be preceded by a `mocha` dependency.
+fn mocha_object(package: &str) -> Option<&str> {
+    lazy_static! {
+        static ref MOCHA_KEY: Regex = Regex::new(r#""mocha"\s*:\s*\{"#).unwrap();
+    }
+
+    let start = MOCHA_KEY.find(package)?.end();
+    let mut depth = 1;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, c) in package[start..].char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '{' if !in_string => depth += 1,
+            '}' if !in_string => {
+                depth -= 1;
+
+                if depth == 0 {
+                    return Some(&package[start..start + i]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Extracts the values of the `spec` option, from the JSON, YAML or JS source
+/// of a Mocha configuration.
+fn spec_globs(config: &str) -> Vec<String> {
+    lazy_static! {
+        static ref SPEC: Regex = Regex::new(
+            r#"["']?\bspec["']?\s*:[ \t]*(\[(?P<list>[^\]]*)\]|(?P<block>(\n[ \t]*-.*)+)|(?P<scalar>.+))"#
+        )
+        .unwrap();
+        static ref VALUE: Regex =
+            Regex::new(r#"(?m)["'](?P<quoted>[^"']*)["']|^[ \t]*-[ \t]*(?P<bare>[^\s#"'][^\s#]*)"#)
+                .unwrap();
+    }
+
+    let captures = match SPEC.captures(config) {
+        Some(captures) => captures,
+        None => return vec![],
+    };
+
+    if let Some(scalar) = captures.name("scalar") {
+        let scalar = scalar.as_str().trim().trim_end_matches(',');
+
+        return VALUE
+            .captures(scalar)
+            .and_then(|value| value.name("quoted"))
+            .map(|value| value.as_str())
+            .or_else(|| scalar.split_whitespace().next())
+            .map(|value| vec![value.to_string()])
+            .unwrap_or_default();
+    }
+
+    let values = captures
+        .name("list")
+        .or_else(|| captures.name("block"))
+        .map(|values| values.as_str())
+        .unwrap_or_default();
+
+    VALUE
+        .captures_iter(values)
+        .filter_map(|value| value.name("quoted").or_else(|| value.name("bare")))
+        .map(|value| value.as_str().to_string())
+        .collect()
+}
+
+/// Converts a spec glob into a regex matching the paths it selects. A glob
+/// without wildcards or an extension is a directory of spec files.
+fn glob_to_regex(glob: &str) -> String {
+    let glob = glob.trim_start_matches("./").trim_end_matches('/');
+    let is_directory = !glob.contains(['*', '?', '{'])
+        && !glob.rsplit('/').next().unwrap_or_default().contains('.');
+
+    let mut regex = String::from("^(\\./)?");
+    let mut chars = glob.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '{' => regex.push('('),
+            '}' => regex.push(')'),
+            ',' => regex.push('|'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    if is_directory {
+        regex.push_str("/[^/]*\\.[cm]?js");
+    }
+    regex.push('$');
+
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_env;
+
+    const TEST_CONTENT: &str = r##"const assert = require("assert");
+
+describe("Array", function () {
+  context("#indexOf()", function () {
+    it("returns -1 when missing", function () {
+      assert.equal([1, 2, 3].indexOf(4), -1);
+    });
+  });
+});
+"##;
+
+    #[test]
+    fn selector_matches_default_spec_files() {
+        test_env::with(|env| {
+            env.write_file("test/array.js", TEST_CONTENT);
+            env.write_file("test/helpers/setup.js", "");
+            let adapter = JsMocha::default();
+            let selector = env.selector("test/array.js:6");
+
+            assert_eq!(SelectorMatch::None, adapter.selector_matches(&selector));
+
+            env.write_file(
+                "package.json",
+                r#"{ "devDependencies": { "mocha": "^10" } }"#,
+            );
+
+            assert_eq!(
+                SelectorMatch::Exclusive,
+                adapter.selector_matches(&selector)
+            );
+            assert_eq!(
+                SelectorMatch::None,
+                adapter.selector_matches(&env.selector("test/helpers/setup.js"))
+            );
+            assert_eq!(
+                SelectorMatch::Shared,
+                adapter.selector_matches(&env.selector("returns -1"))
+            );
+        })
+    }
+
+    #[test]
+    fn selector_matches_configured_spec_globs() {
+        for (file, content) in [
+            (".mocharc.json", r#"{ "spec": ["spec/**/*.spec.js"] }"#),
+            (".mocharc.yml", "spec:\n  - 'spec/**/*.spec.js'\n"),
+            (
+                ".mocharc.js",
+                "module.exports = { spec: 'spec/**/*.spec.js' };\n",
+            ),
+            (
+                "package.json",
+                r#"{ "mocha": { "spec": "spec/**/*.spec.js" }, "scripts": {} }"#,
+            ),
+            (
+                "package.json",
+                r#"{
+                  "devDependencies": { "mocha": "^10" },
+                  "mocha": { "require": { "ts": "ts-node/register" }, "spec": ["spec/**/*.spec.{js,ts}"] }
+                }"#,
+            ),
+        ] {
+            test_env::with(|env| {
+                env.write_file(file, content);
+                env.write_file("spec/models/user.spec.js", TEST_CONTENT);
+                env.write_file("test/array.js", TEST_CONTENT);
+                let adapter = JsMocha::default();
+
+                assert_eq!(
+                    SelectorMatch::Exclusive,
+                    adapter.selector_matches(&env.selector("spec/models/user.spec.js:6"))
+                );
+                assert_eq!(
+                    SelectorMatch::None,
+                    adapter.selector_matches(&env.selector("test/array.js:6")),
+                    "{file}"
+                );
+            })
+        }
+    }
+
+    #[test]
+    fn collect_commands_path_with_line_number() {
+        test_env::with(|env| {
+            env.write_file("test/array.js", TEST_CONTENT);
+
+            assert_eq!(
+                env.commands(&JsMocha::default(), &["test/array.js:6"]),
+                vec![
+                    r"npx mocha test/array.js --grep '^Array \#indexOf\(\) returns \-1 when missing$'"
+                ]
+            );
+            assert_eq!(
+                env.commands(&JsMocha::default(), &["test/array.js:8"]),
+                vec![r"npx mocha test/array.js --grep '^Array \#indexOf\(\) '"]
+            );
+            assert_eq!(
+                env.commands(&JsMocha::default(), &["test/array.js:9"]),
+                vec!["npx mocha test/array.js --grep '^Array '"]
+            );
+            assert_eq!(
+                env.commands(&JsMocha::default(), &["test/array.js:1"]),
+                vec!["npx mocha test/array.js"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_name_only() {
+        test_env::with(|env| {
+            env.write_file("yarn.lock", "");
+
+            assert_eq!(
+                env.commands(&JsMocha::default(), &["returns -1"]),
+                vec!["yarn mocha --grep 'returns -1'"]
+            );
+        })
+    }
+
+    #[test]
+    fn spec_globs_works() {
+        for config in [
+            r#"{ "spec": ["spec/**/*.spec.js", "test/*.js"] }"#,
+            "spec:\n  - 'spec/**/*.spec.js'\n  - test/*.js\n",
+            "module.exports = {\n  spec: ['spec/**/*.spec.js', 'test/*.js'],\n};\n",
+        ] {
+            assert_eq!(
+                vec!["spec/**/*.spec.js", "test/*.js"],
+                spec_globs(config),
+                "{config}"
+            );
+        }
+
+        assert_eq!(
+            vec!["test/unit"],
+            spec_globs("spec: test/unit # unit tests\n")
+        );
+        assert_eq!(Vec::<String>::new(), spec_globs("{ \"ui\": \"bdd\" }"));
+    }
+
+    #[test]
+    fn glob_to_regex_works() {
+        for (glob, path, expected) in [
+            ("./test/*.{js,mjs}", "test/foo.mjs", true),
+            ("./test/*.{js,mjs}", "test/foo/bar.js", false),
+            ("test/**/*.spec.js", "test/foo.spec.js", true),
+            ("test/**/*.spec.js", "test/a/b/foo.spec.js", true),
+            ("test/unit", "test/unit/foo.js", true),
+            ("test/unit/", "test/unit/foo.ts", false),
+        ] {
+            let regex = Regex::new(&glob_to_regex(glob)).unwrap();
+
+            assert_eq!(expected, regex.is_match(path), "{glob} {path}");
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use super::{
+    bun_test::uses_bun,
+    deno_test::uses_deno,
+    js_jest::{find_name_pattern, js_package_command},
+    SelectorMatch, TestAdapter,
+};
+use crate::{
+    config::{AdapterConfig, Config},
+    memoize_string, none_if_empty,
+    selector_match::{exclusive_or_none, shared_or_none},
+    utils::{file_exists, is_in_file},
+    TestSelector,
+};
+use std::borrow::Cow;
+use TestSelector::*;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct JsVitest {
+    config: AdapterConfig,
+}
+
+impl JsVitest {
+    /// Creates a new JsVitest adapter.
+    pub const fn new(config: AdapterConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Default for JsVitest {
+    fn default() -> Self {
+        Self::new(Config::default().js_vitest.clone())
+    }
+}
+
+impl TestAdapter for JsVitest {
+    fn selector_matches(&self, selector: &TestSelector) -> SelectorMatch {
+        if uses_bun() || uses_deno() {
+            return SelectorMatch::None;
+        }
+
+        match selector {
+            PathWithLineNumber { path, .. } | PathOnly { path } => {
+                exclusive_or_none(uses_vitest() && path.matches(&self.config.file_patterns))
+            }
+            NameOnly { .. } => shared_or_none(uses_vitest()),
+        }
+    }
+
+    fn collect_commands(&self, selectors: &[&TestSelector]) -> Option<Vec<Cow<'_, str>>> {
+        let mut commands: Vec<Cow<str>> = vec![];
+
+        for selector in selectors {
+            let mut args = vec![];
+
+            match selector {
+                PathWithLineNumber { path, line } => {
+                    args.push(path.to_string_lossy().to_string());
+
+                    if let Some(pattern) = find_name_pattern(&self.config, path, *line) {
+                        args.extend(["-t".to_string(), pattern]);
+                    }
+                }
+                PathOnly { path } => args.push(path.to_string_lossy().to_string()),
+                NameOnly { name } => args.extend(["-t".to_string(), name.to_string()]),
+            };
+
+            commands.push(format!("{} {}", vitest_command(), shell_words::join(args)).into());
+        }
+
+        none_if_empty!(commands)
+    }
+}
+
+/// Returns true if the project runs its tests with Vitest, rather than Jest.
+pub(super) fn uses_vitest() -> bool {
+    ["ts", "mts", "cts", "js", "mjs", "cjs"]
+        .iter()
+        .any(|ext| file_exists(format!("vitest.config.{ext}")))
+        || is_in_file("package.json", "\"vitest\"")
+}
+
+fn vitest_command() -> Cow<'static, str> {
+    memoize_string!(format!("{} run", js_package_command("vitest")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_env;
+
+    const TEST_CONTENT: &str = r#"import { describe, expect, it } from "vitest";
+
+describe("add", () => {
+  it("adds numbers", () => {
+    expect(1 + 2).toBe(3);
+  });
+});
+"#;
+
+    #[test]
+    fn selector_matches_with_vitest_markers() {
+        for (file, content) in [
+            ("vitest.config.ts", ""),
+            ("vitest.config.mjs", ""),
+            (
+                "package.json",
+                r#"{ "devDependencies": { "vitest": "^1" } }"#,
+            ),
+        ] {
+            test_env::with(|env| {
+                env.write_file("src/add.test.ts", TEST_CONTENT);
+                let adapter = JsVitest::default();
+                let selector = env.selector("src/add.test.ts:5");
+
+                assert_eq!(SelectorMatch::None, adapter.selector_matches(&selector));
+
+                env.write_file(file, content);
+
+                assert_eq!(
+                    SelectorMatch::Exclusive,
+                    adapter.selector_matches(&selector)
+                );
+                assert_eq!(
+                    SelectorMatch::Shared,
+                    adapter.selector_matches(&env.selector("adds numbers"))
+                );
+            })
+        }
+    }
+
+    #[test]
+    fn collect_commands_path_with_line_number() {
+        test_env::with(|env| {
+            env.write_file("src/add.test.ts", TEST_CONTENT);
+
+            assert_eq!(
+                env.commands(&JsVitest::default(), &["src/add.test.ts:5"]),
+                vec!["npx vitest run src/add.test.ts -t '^add adds numbers$'"]
+            );
+            assert_eq!(
+                env.commands(&JsVitest::default(), &["src/add.test.ts:7"]),
+                vec!["npx vitest run src/add.test.ts -t '^add '"]
+            );
+            assert_eq!(
+                env.commands(&JsVitest::default(), &["src/add.test.ts:1"]),
+                vec!["npx vitest run src/add.test.ts"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_name_only() {
+        test_env::with(|env| {
+            env.write_file("pnpm-lock.yaml", "");
+
+            assert_eq!(
+                env.commands(&JsVitest::default(), &["adds numbers"]),
+                vec!["pnpm vitest run -t 'adds numbers'"]
+            );
+        })
+    }
+}
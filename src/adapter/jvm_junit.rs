@@ -0,0 +1,478 @@
+use super::{SelectorMatch, TestAdapter};
+use crate::{
+    config::{AdapterConfig, Config},
+    none_if_empty,
+    selector_match::{exclusive_or_none, shared_or_none},
+    test_file::TestFile,
+    test_index::TestIndex,
+    utils::file_exists,
+    TestSelector,
+};
+use std::{
+    borrow::Cow,
+    env::current_dir,
+    fs::read_to_string,
+    path::{Component, Path},
+};
+use TestSelector::*;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct JvmJunit {
+    config: AdapterConfig,
+}
+
+impl JvmJunit {
+    /// Creates a new JvmJunit adapter.
+    pub const fn new(config: AdapterConfig) -> Self {
+        Self { config }
+    }
+
+    /// Finds the test class, and the `@Test` method enclosing the given line.
+    /// Falls back to the class named after the file if the line is outside of
+    /// any class.
+    fn find_test(&self, path: &TestFile, line: u32) -> JvmTest {
+        let mut test = JvmTest::of_file(path);
+
+        let test_index = match TestIndex::build(path, &self.config) {
+            Ok(test_index) => test_index,
+            Err(_) => return test,
+        };
+        let enclosing = match test_index.enclosing(line) {
+            Some(enclosing) => enclosing,
+            None => return test,
+        };
+
+        let mut classes = test_index
+            .ancestors(enclosing)
+            .iter()
+            .filter_map(|class| class.name())
+            .map(String::from)
+            .collect::<Vec<_>>();
+
+        if enclosing.is_test() {
+            test.method = enclosing.name().map(String::from);
+        } else {
+            classes.extend(enclosing.name().map(String::from));
+        }
+        if !classes.is_empty() {
+            test.class = classes.join("$");
+        }
+
+        test
+    }
+}
+
+impl Default for JvmJunit {
+    fn default() -> Self {
+        Self::new(Config::default().jvm_junit.clone())
+    }
+}
+
+impl TestAdapter for JvmJunit {
+    fn selector_matches(&self, selector: &TestSelector) -> SelectorMatch {
+        match selector {
+            PathWithLineNumber { path, .. } | PathOnly { path } => {
+                exclusive_or_none(path.matches(&self.config.file_patterns))
+            }
+            NameOnly { .. } => shared_or_none(BuildTool::detect(Path::new("")).is_some()),
+        }
+    }
+
+    fn collect_commands(&self, selectors: &[&TestSelector]) -> Option<Vec<Cow<'_, str>>> {
+        let mut commands: Vec<Cow<str>> = vec![];
+
+        for selector in selectors {
+            let command = match selector {
+                PathWithLineNumber { path, line } => {
+                    JvmModule::locate(path).command(&self.find_test(path, *line))
+                }
+                PathOnly { path } => JvmModule::locate(path).command(&JvmTest::of_file(path)),
+                NameOnly { name } => JvmModule::name_command(name),
+            };
+
+            commands.extend(command.map(Cow::from));
+        }
+
+        none_if_empty!(commands)
+    }
+}
+
+/// A JUnit test class, and optionally one of its methods.
+#[derive(Debug, PartialEq, Eq)]
+struct JvmTest {
+    /// The package the class is declared in.
+    package: Option<String>,
+    /// The name of the class, with nested classes separated by `$`.
+    class: String,
+    /// The name of the test method.
+    method: Option<String>,
+}
+
+impl JvmTest {
+    /// Returns the class named after the file, in the package it declares.
+    fn of_file(path: &TestFile) -> Self {
+        let class = Path::new(path.as_ref())
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        Self {
+            package: package_name(path),
+            class,
+            method: None,
+        }
+    }
+
+    /// Returns the fully qualified name of the class.
+    fn qualified_class(&self) -> String {
+        match &self.package {
+            Some(package) => format!("{package}.{}", self.class),
+            None => self.class.clone(),
+        }
+    }
+}
+
+/// The build tools which can run JUnit tests.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum BuildTool {
+    Maven,
+    Gradle,
+}
+
+impl BuildTool {
+    /// Detects the build tool of the module in the given directory, relative to
+    /// the current directory.
+    fn detect(dir: &Path) -> Option<Self> {
+        if [
+            "build.gradle",
+            "build.gradle.kts",
+            "settings.gradle",
+            "settings.gradle.kts",
+        ]
+        .iter()
+        .any(|file| file_exists(dir.join(file)))
+        {
+            Some(Self::Gradle)
+        } else if file_exists(dir.join("pom.xml")) {
+            Some(Self::Maven)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the wrapper script if the project has one, or the globally
+    /// installed build tool.
+    fn executable(self) -> &'static str {
+        match self {
+            Self::Maven if file_exists("mvnw") => "./mvnw",
+            Self::Maven => "mvn",
+            Self::Gradle if file_exists("gradlew") => "./gradlew",
+            Self::Gradle => "gradle",
+        }
+    }
+}
+
+/// The Maven or Gradle module a test file belongs to.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct JvmModule {
+    /// The build tool of the module, if one was found.
+    tool: Option<BuildTool>,
+    /// The path of the module's directory, relative to the current directory.
+    /// Empty for the root module.
+    path: Vec<String>,
+}
+
+impl JvmModule {
+    /// Locates the module of the file, using the nearest directory with a
+    /// build file.
+    fn locate(path: &TestFile) -> Self {
+        let cwd = current_dir().unwrap_or_default();
+        let file = cwd.join(path);
+        let relative = match file.strip_prefix(&cwd) {
+            Ok(relative) => relative,
+            Err(_) => return Self::default(),
+        };
+
+        relative
+            .ancestors()
+            .skip(1)
+            .find_map(|dir| {
+                let tool = BuildTool::detect(dir)?;
+                let path = dir
+                    .components()
+                    .filter_map(|component| match component {
+                        Component::Normal(segment) => segment.to_str().map(String::from),
+                        _ => None,
+                    })
+                    .collect();
+
+                Some(Self {
+                    tool: Some(tool),
+                    path,
+                })
+            })
+            .unwrap_or_default()
+    }
+
+    /// Builds the command to run the given test in this module.
+    fn command(&self, test: &JvmTest) -> Option<String> {
+        let args = match self.tool? {
+            BuildTool::Maven => {
+                let mut filter = test.class.clone();
+
+                if let Some(method) = &test.method {
+                    filter = format!("{filter}#{method}");
+                }
+
+                self.maven_args(filter)
+            }
+            BuildTool::Gradle => {
+                let mut filter = test.qualified_class();
+
+                if let Some(method) = &test.method {
+                    filter = format!("{filter}.{method}");
+                }
+
+                self.gradle_args(filter)
+            }
+        };
+
+        Some(shell_words::join(args))
+    }
+
+    /// Builds the command to run the tests matching a name, in the project in
+    /// the current directory.
+    fn name_command(name: &str) -> Option<String> {
+        let tool = BuildTool::detect(Path::new(""))?;
+        let module = Self {
+            tool: Some(tool),
+            path: vec![],
+        };
+
+        module.command(&JvmTest {
+            package: None,
+            class: format!("*{name}*"),
+            method: None,
+        })
+    }
+
+    fn maven_args(&self, filter: String) -> Vec<String> {
+        let mut args = vec![BuildTool::Maven.executable().to_string()];
+
+        if !self.path.is_empty() {
+            args.extend(["-pl".to_string(), self.path.join("/")]);
+        }
+        args.extend([format!("-Dtest={filter}"), "test".to_string()]);
+
+        args
+    }
+
+    fn gradle_args(&self, filter: String) -> Vec<String> {
+        let task = if self.path.is_empty() {
+            "test".to_string()
+        } else {
+            format!(":{}:test", self.path.join(":"))
+        };
+
+        vec![
+            BuildTool::Gradle.executable().to_string(),
+            task,
+            "--tests".to_string(),
+            filter,
+        ]
+    }
+}
+
+/// Reads the package a Java or Kotlin file declares, if any.
+fn package_name(path: &TestFile) -> Option<String> {
+    let content = read_to_string(path).ok()?;
+
+    content.lines().find_map(|line| {
+        let package = line.trim().strip_prefix("package ")?;
+
+        Some(package.trim_end_matches(';').trim().to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_env;
+
+    const JAVA_CONTENT: &str = r#"package com.acme;
+
+import org.junit.jupiter.api.Nested;
+import org.junit.jupiter.api.Test;
+
+class FooTest {
+    private int helper() {
+        return 1;
+    }
+
+    @Test
+    void addsNumbers() {
+        assertEquals(2, helper() + 1);
+    }
+
+    @Nested
+    class WhenEmpty {
+        @Test
+        @DisplayName("is empty")
+        public void isEmpty() throws Exception {
+            assertTrue(true);
+        }
+    }
+}
+"#;
+
+    const KOTLIN_CONTENT: &str = r#"package com.acme
+
+import kotlin.test.Test
+
+class BarTest {
+    @Test
+    fun `adds numbers`() {
+        assertEquals(2, 1 + 1)
+    }
+}
+"#;
+
+    #[test]
+    fn selector_matches_test_sources() {
+        test_env::with(|env| {
+            env.write_file("pom.xml", "");
+            env.write_file("src/test/java/com/acme/FooTest.java", JAVA_CONTENT);
+            env.write_file("src/test/kotlin/com/acme/BarTest.kt", KOTLIN_CONTENT);
+            env.write_file("src/main/java/com/acme/Foo.java", "");
+            let adapter = JvmJunit::default();
+
+            assert_eq!(
+                SelectorMatch::Exclusive,
+                adapter.selector_matches(&env.selector("src/test/java/com/acme/FooTest.java:13"))
+            );
+            assert_eq!(
+                SelectorMatch::Exclusive,
+                adapter.selector_matches(&env.selector("src/test/kotlin/com/acme/BarTest.kt"))
+            );
+            assert_eq!(
+                SelectorMatch::None,
+                adapter.selector_matches(&env.selector("src/main/java/com/acme/Foo.java"))
+            );
+            assert_eq!(
+                SelectorMatch::Shared,
+                adapter.selector_matches(&env.selector("addsNumbers"))
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_with_maven() {
+        test_env::with(|env| {
+            env.write_file("pom.xml", "");
+            env.write_file("mvnw", "");
+            env.write_file("src/test/java/com/acme/FooTest.java", JAVA_CONTENT);
+
+            assert_eq!(
+                env.commands(
+                    &JvmJunit::default(),
+                    &["src/test/java/com/acme/FooTest.java:13"]
+                ),
+                vec!["./mvnw '-Dtest=FooTest#addsNumbers' test"]
+            );
+            assert_eq!(
+                env.commands(
+                    &JvmJunit::default(),
+                    &["src/test/java/com/acme/FooTest.java:21"]
+                ),
+                vec!["./mvnw '-Dtest=FooTest$WhenEmpty#isEmpty' test"]
+            );
+            assert_eq!(
+                env.commands(
+                    &JvmJunit::default(),
+                    &["src/test/java/com/acme/FooTest.java:8"]
+                ),
+                vec!["./mvnw '-Dtest=FooTest' test"]
+            );
+            assert_eq!(
+                env.commands(
+                    &JvmJunit::default(),
+                    &["src/test/java/com/acme/FooTest.java"]
+                ),
+                vec!["./mvnw '-Dtest=FooTest' test"]
+            );
+            assert_eq!(
+                env.commands(&JvmJunit::default(), &["addsNumbers"]),
+                vec!["./mvnw '-Dtest=*addsNumbers*' test"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_with_gradle() {
+        test_env::with(|env| {
+            env.write_file("build.gradle.kts", "");
+            env.write_file("gradlew", "");
+            env.write_file("src/test/java/com/acme/FooTest.java", JAVA_CONTENT);
+            env.write_file("src/test/kotlin/com/acme/BarTest.kt", KOTLIN_CONTENT);
+
+            assert_eq!(
+                env.commands(
+                    &JvmJunit::default(),
+                    &["src/test/java/com/acme/FooTest.java:13"]
+                ),
+                vec!["./gradlew test --tests com.acme.FooTest.addsNumbers"]
+            );
+            assert_eq!(
+                env.commands(
+                    &JvmJunit::default(),
+                    &["src/test/java/com/acme/FooTest.java:21"]
+                ),
+                vec!["./gradlew test --tests 'com.acme.FooTest$WhenEmpty.isEmpty'"]
+            );
+            assert_eq!(
+                env.commands(
+                    &JvmJunit::default(),
+                    &["src/test/kotlin/com/acme/BarTest.kt:8"]
+                ),
+                vec!["./gradlew test --tests 'com.acme.BarTest.adds numbers'"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_in_submodules() {
+        test_env::with(|env| {
+            env.write_file("pom.xml", "");
+            env.write_file("services/api/pom.xml", "");
+            env.write_file(
+                "services/api/src/test/java/com/acme/FooTest.java",
+                JAVA_CONTENT,
+            );
+
+            assert_eq!(
+                env.commands(
+                    &JvmJunit::default(),
+                    &["services/api/src/test/java/com/acme/FooTest.java:13"]
+                ),
+                vec!["mvn -pl services/api '-Dtest=FooTest#addsNumbers' test"]
+            );
+        });
+
+        test_env::with(|env| {
+            env.write_file("settings.gradle", "");
+            env.write_file("services/api/build.gradle", "");
+            env.write_file(
+                "services/api/src/test/java/com/acme/FooTest.java",
+                JAVA_CONTENT,
+            );
+
+            assert_eq!(
+                env.commands(
+                    &JvmJunit::default(),
+                    &["services/api/src/test/java/com/acme/FooTest.java:13"]
+                ),
+                vec!["gradle :services:api:test --tests com.acme.FooTest.addsNumbers"]
+            );
+        })
+    }
+}
@@ -0,0 +1,227 @@
+use super::{SelectorMatch, TestAdapter};
+use crate::{
+    config::{AdapterConfig, Config},
+    memoize_string, none_if_empty,
+    selector_match::{exclusive_or_none, shared_or_none},
+    test_file::TestFile,
+    test_index::TestIndex,
+    utils::file_exists,
+    TestSelector,
+};
+use std::{
+    borrow::Cow,
+    env::current_dir,
+    fs::{read_dir, read_to_string},
+};
+use TestSelector::*;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct LuaBusted {
+    config: AdapterConfig,
+}
+
+impl LuaBusted {
+    /// Creates a new LuaBusted adapter.
+    pub const fn new(config: AdapterConfig) -> Self {
+        Self { config }
+    }
+
+    /// Finds the `--filter` for the `it` or `describe` block enclosing the
+    /// given line, as a Lua pattern matching its full name. Returns `None` if
+    /// the whole file should be run.
+    fn find_filter(&self, path: &TestFile, line: u32) -> Option<String> {
+        let test_index = TestIndex::build(path, &self.config).ok()?;
+        let enclosing = test_index.enclosing(line)?;
+
+        let ancestors = test_index.ancestors(enclosing);
+        let full_name = ancestors
+            .iter()
+            .chain([&enclosing])
+            .filter_map(|entry| entry.name())
+            .map(escape_lua_pattern)
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        if enclosing.is_test() {
+            Some(format!("^{full_name}$"))
+        } else {
+            Some(format!("^{full_name} "))
+        }
+    }
+}
+
+impl Default for LuaBusted {
+    fn default() -> Self {
+        Self::new(Config::default().lua_busted.clone())
+    }
+}
+
+impl TestAdapter for LuaBusted {
+    fn selector_matches(&self, selector: &TestSelector) -> SelectorMatch {
+        match selector {
+            PathWithLineNumber { path, .. } | PathOnly { path } => {
+                exclusive_or_none(path.matches(&self.config.file_patterns))
+            }
+            NameOnly { .. } => shared_or_none(file_exists(".busted") || rockspec_uses_busted()),
+        }
+    }
+
+    fn collect_commands(&self, selectors: &[&TestSelector]) -> Option<Vec<Cow<'_, str>>> {
+        let mut commands: Vec<Cow<str>> = vec![];
+
+        for selector in selectors {
+            let mut args = vec![];
+
+            match selector {
+                PathWithLineNumber { path, line } => {
+                    if let Some(filter) = self.find_filter(path, *line) {
+                        args.extend(["--filter".to_string(), filter]);
+                    }
+                    args.push(path.to_string_lossy().to_string());
+                }
+                PathOnly { path } => args.push(path.to_string_lossy().to_string()),
+                NameOnly { name } => args.extend(["--filter".to_string(), name.to_string()]),
+            };
+
+            commands.push(format!("{} {}", busted_command(), shell_words::join(args)).into());
+        }
+
+        none_if_empty!(commands)
+    }
+}
+
+/// Returns true if a rockspec in the current directory declares busted, so
+/// `luarocks test` runs it.
+fn rockspec_uses_busted() -> bool {
+    let entries = match current_dir().and_then(read_dir) {
+        Ok(entries) => entries,
+        Err(_) => return false,
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .path()
+                .extension()
+                .is_some_and(|ext| ext == "rockspec")
+        })
+        .filter_map(|entry| read_to_string(entry.path()).ok())
+        .any(|rockspec| rockspec.contains("busted"))
+}
+
+fn busted_command() -> Cow<'static, str> {
+    memoize_string!({
+        if rockspec_uses_busted() {
+            "luarocks test --"
+        } else {
+            "busted"
+        }
+    })
+}
+
+/// Escapes the magic characters of a Lua pattern.
+fn escape_lua_pattern(text: &str) -> String {
+    text.chars()
+        .flat_map(|c| match c {
+            '^' | '$' | '(' | ')' | '%' | '.' | '[' | ']' | '*' | '+' | '-' | '?' => {
+                vec!['%', c]
+            }
+            c => vec![c],
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_env;
+
+    const TEST_CONTENT: &str = r#"local plugin = require("plugin")
+
+describe("plugin", function()
+  describe("setup()", function()
+    it("uses the defaults", function()
+      assert.are.same({}, plugin.setup())
+    end)
+  end)
+
+  it('is 100% lua', function()
+    assert.truthy(plugin)
+  end)
+end)
+"#;
+
+    #[test]
+    fn selector_matches_spec_files() {
+        test_env::with(|env| {
+            env.write_file("spec/plugin_spec.lua", TEST_CONTENT);
+            env.write_file("lua/plugin.lua", "");
+            let adapter = LuaBusted::default();
+
+            assert_eq!(
+                SelectorMatch::Exclusive,
+                adapter.selector_matches(&env.selector("spec/plugin_spec.lua:6"))
+            );
+            assert_eq!(
+                SelectorMatch::None,
+                adapter.selector_matches(&env.selector("lua/plugin.lua"))
+            );
+            assert_eq!(
+                SelectorMatch::None,
+                adapter.selector_matches(&env.selector("uses the defaults"))
+            );
+
+            env.write_file(".busted", "return {}");
+
+            assert_eq!(
+                SelectorMatch::Shared,
+                adapter.selector_matches(&env.selector("uses the defaults"))
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_path_with_line_number() {
+        test_env::with(|env| {
+            env.write_file("spec/plugin_spec.lua", TEST_CONTENT);
+
+            assert_eq!(
+                env.commands(&LuaBusted::default(), &["spec/plugin_spec.lua:6"]),
+                vec!["busted --filter '^plugin setup%(%) uses the defaults$' spec/plugin_spec.lua"]
+            );
+            assert_eq!(
+                env.commands(&LuaBusted::default(), &["spec/plugin_spec.lua:11"]),
+                vec!["busted --filter '^plugin is 100%% lua$' spec/plugin_spec.lua"]
+            );
+            assert_eq!(
+                env.commands(&LuaBusted::default(), &["spec/plugin_spec.lua:9"]),
+                vec!["busted --filter '^plugin ' spec/plugin_spec.lua"]
+            );
+            assert_eq!(
+                env.commands(&LuaBusted::default(), &["spec/plugin_spec.lua:1"]),
+                vec!["busted spec/plugin_spec.lua"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_with_luarocks() {
+        test_env::with(|env| {
+            env.write_file(
+                "plugin-scm-1.rockspec",
+                "test_dependencies = { \"busted\" }\ntest = { type = \"busted\" }\n",
+            );
+            env.write_file("spec/plugin_spec.lua", TEST_CONTENT);
+
+            assert_eq!(
+                env.commands(&LuaBusted::default(), &["spec/plugin_spec.lua"]),
+                vec!["luarocks test -- spec/plugin_spec.lua"]
+            );
+            assert_eq!(
+                env.commands(&LuaBusted::default(), &["uses the defaults"]),
+                vec!["luarocks test -- --filter 'uses the defaults'"]
+            );
+        })
+    }
+}
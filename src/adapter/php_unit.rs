@@ -0,0 +1,272 @@
+use super::{SelectorMatch, TestAdapter};
+use crate::{
+    config::{AdapterConfig, Config},
+    none_if_empty,
+    selector_match::{exclusive_or_none, shared_or_none},
+    test_file::TestFile,
+    test_index::TestIndex,
+    utils::{file_exists, is_in_file},
+    TestSelector,
+};
+use std::borrow::Cow;
+use TestSelector::*;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct PhpUnit {
+    config: AdapterConfig,
+}
+
+impl PhpUnit {
+    /// Creates a new PhpUnit adapter.
+    pub const fn new(config: AdapterConfig) -> Self {
+        Self { config }
+    }
+
+    /// Finds the `--filter` for the test enclosing the given line. Test methods
+    /// are filtered by `Class::method`, and Pest closures by their description.
+    /// PHPUnit matches the filter as a regex, so method filters are anchored
+    /// at the end to keep `testAdd` from also running `testAddsMore`.
+    /// Returns `None` if the whole file should be run.
+    fn find_filter(&self, path: &TestFile, line: u32) -> Option<String> {
+        let test_index = TestIndex::build(path, &self.config).ok()?;
+        let enclosing = test_index.enclosing(line)?;
+
+        if !enclosing.is_test() {
+            return None;
+        }
+
+        let name = enclosing.name()?;
+        let content = enclosing.content().trim_start();
+
+        if content.starts_with("it(") {
+            return Some(regex::escape(&format!("it {name}")));
+        } else if content.starts_with("test(") {
+            return Some(regex::escape(name));
+        }
+
+        let method = match test_index.ancestors(enclosing).last() {
+            Some(class) => format!("{}::{name}", class.name()?),
+            None => name.to_string(),
+        };
+
+        Some(format!("{method}( with data set .*)?$"))
+    }
+}
+
+impl Default for PhpUnit {
+    fn default() -> Self {
+        Self::new(Config::default().php_unit.clone())
+    }
+}
+
+impl TestAdapter for PhpUnit {
+    fn selector_matches(&self, selector: &TestSelector) -> SelectorMatch {
+        match selector {
+            PathWithLineNumber { path, .. } | PathOnly { path } => {
+                exclusive_or_none(path.matches(&self.config.file_patterns))
+            }
+            NameOnly { .. } => shared_or_none(file_exists("composer.json")),
+        }
+    }
+
+    fn collect_commands(&self, selectors: &[&TestSelector]) -> Option<Vec<Cow<'_, str>>> {
+        let mut commands: Vec<Cow<str>> = vec![];
+
+        for selector in selectors {
+            let mut args = vec![phpunit_command().to_string()];
+
+            match selector {
+                PathWithLineNumber { path, line } => {
+                    if let Some(filter) = self.find_filter(path, *line) {
+                        args.extend(["--filter".to_string(), filter]);
+                    }
+                    args.push(path.to_string_lossy().to_string());
+                }
+                PathOnly { path } => args.push(path.to_string_lossy().to_string()),
+                NameOnly { name } => args.extend(["--filter".to_string(), name.to_string()]),
+            };
+
+            commands.push(shell_words::join(args).into());
+        }
+
+        none_if_empty!(commands)
+    }
+}
+
+/// Returns Pest if the project depends on it, since it also runs the PHPUnit
+/// tests, or PHPUnit otherwise.
+fn phpunit_command() -> &'static str {
+    if is_in_file("composer.json", "pestphp/pest") {
+        "vendor/bin/pest"
+    } else {
+        "vendor/bin/phpunit"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_env;
+
+    const TEST_CONTENT: &str = r#"<?php
+
+namespace Tests\Unit;
+
+use PHPUnit\Framework\TestCase;
+
+final class FooTest extends TestCase
+{
+    private function helper(): int
+    {
+        return 1;
+    }
+
+    public function testAddsNumbers(): void
+    {
+        $this->assertSame(2, $this->helper() + 1);
+    }
+
+    /**
+     * @test
+     */
+    public function it_subtracts_numbers(): void
+    {
+        $this->assertSame(0, $this->helper() - 1);
+    }
+}
+"#;
+
+    const PEST_CONTENT: &str = r#"<?php
+
+it('adds numbers', function () {
+    expect(1 + 1)->toBe(2);
+});
+
+test('sum (of many)', function () {
+    expect(array_sum([1, 2]))->toBe(3);
+});
+"#;
+
+    #[test]
+    fn selector_matches_test_files() {
+        test_env::with(|env| {
+            env.write_file("composer.json", "{}");
+            env.write_file("tests/Unit/FooTest.php", TEST_CONTENT);
+            env.write_file("app/Foo.php", "");
+            let adapter = PhpUnit::default();
+
+            assert_eq!(
+                SelectorMatch::Exclusive,
+                adapter.selector_matches(&env.selector("tests/Unit/FooTest.php:16"))
+            );
+            assert_eq!(
+                SelectorMatch::None,
+                adapter.selector_matches(&env.selector("app/Foo.php"))
+            );
+            assert_eq!(
+                SelectorMatch::Shared,
+                adapter.selector_matches(&env.selector("testAddsNumbers"))
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_path_with_line_number() {
+        test_env::with(|env| {
+            env.write_file("tests/Unit/FooTest.php", TEST_CONTENT);
+
+            assert_eq!(
+                env.commands(&PhpUnit::default(), &["tests/Unit/FooTest.php:16"]),
+                vec!["vendor/bin/phpunit --filter 'FooTest::testAddsNumbers( with data set .*)?$' tests/Unit/FooTest.php"]
+            );
+            assert_eq!(
+                env.commands(&PhpUnit::default(), &["tests/Unit/FooTest.php:24"]),
+                vec!["vendor/bin/phpunit --filter 'FooTest::it_subtracts_numbers( with data set .*)?$' tests/Unit/FooTest.php"]
+            );
+            assert_eq!(
+                env.commands(&PhpUnit::default(), &["tests/Unit/FooTest.php:11"]),
+                vec!["vendor/bin/phpunit tests/Unit/FooTest.php"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_anchors_method_names() {
+        test_env::with(|env| {
+            env.write_file(
+                "tests/Unit/SumTest.php",
+                r#"<?php
+
+final class SumTest extends TestCase
+{
+    public function testAdd(): void
+    {
+        $this->assertSame(2, 1 + 1);
+    }
+
+    public function testAddsMore(): void
+    {
+        $this->assertSame(3, 1 + 2);
+    }
+}
+"#,
+            );
+
+            assert_eq!(
+                env.commands(&PhpUnit::default(), &["tests/Unit/SumTest.php:7"]),
+                vec!["vendor/bin/phpunit --filter 'SumTest::testAdd( with data set .*)?$' tests/Unit/SumTest.php"]
+            );
+            assert_eq!(
+                env.commands(&PhpUnit::default(), &["tests/Unit/SumTest.php:12"]),
+                vec!["vendor/bin/phpunit --filter 'SumTest::testAddsMore( with data set .*)?$' tests/Unit/SumTest.php"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_path_only() {
+        test_env::with(|env| {
+            env.write_file("tests/Unit/FooTest.php", TEST_CONTENT);
+
+            assert_eq!(
+                env.commands(&PhpUnit::default(), &["tests/Unit/FooTest.php"]),
+                vec!["vendor/bin/phpunit tests/Unit/FooTest.php"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_name_only() {
+        test_env::with(|env| {
+            assert_eq!(
+                env.commands(&PhpUnit::default(), &["testAddsNumbers"]),
+                vec!["vendor/bin/phpunit --filter testAddsNumbers"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_with_pest() {
+        test_env::with(|env| {
+            env.write_file(
+                "composer.json",
+                r#"{ "require-dev": { "pestphp/pest": "^2.0" } }"#,
+            );
+            env.write_file("tests/Unit/FooTest.php", TEST_CONTENT);
+            env.write_file("tests/Feature/SumTest.php", PEST_CONTENT);
+
+            assert_eq!(
+                env.commands(&PhpUnit::default(), &["tests/Feature/SumTest.php:4"]),
+                vec!["vendor/bin/pest --filter 'it adds numbers' tests/Feature/SumTest.php"]
+            );
+            assert_eq!(
+                env.commands(&PhpUnit::default(), &["tests/Feature/SumTest.php:8"]),
+                vec![r"vendor/bin/pest --filter 'sum \(of many\)' tests/Feature/SumTest.php"]
+            );
+            assert_eq!(
+                env.commands(&PhpUnit::default(), &["tests/Unit/FooTest.php:16"]),
+                vec!["vendor/bin/pest --filter 'FooTest::testAddsNumbers( with data set .*)?$' tests/Unit/FooTest.php"]
+            );
+        })
+    }
+}
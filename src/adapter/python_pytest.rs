@@ -0,0 +1,260 @@
+use super::{SelectorMatch, TestAdapter};
+use crate::{
+    config::{AdapterConfig, Config},
+    memoize_string, none_if_empty,
+    selector_match::{exclusive_or_none, shared_or_none},
+    test_file::TestFile,
+    test_index::TestIndex,
+    utils::{file_exists, is_in_file},
+    TestSelector,
+};
+use std::borrow::Cow;
+use TestSelector::*;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct PythonPytest {
+    config: AdapterConfig,
+}
+
+impl PythonPytest {
+    /// Creates a new PythonPytest adapter.
+    pub const fn new(config: AdapterConfig) -> Self {
+        Self { config }
+    }
+
+    /// Finds the pytest node id of the test function or class enclosing the
+    /// given line, falling back to the file itself.
+    fn find_node_id(&self, path: &TestFile, line: u32) -> String {
+        let mut node_id = path.to_string_lossy().to_string();
+
+        let test_index = match TestIndex::build(path, &self.config) {
+            Ok(test_index) => test_index,
+            Err(_) => return node_id,
+        };
+
+        if let Some(enclosing) = test_index.enclosing(line) {
+            let ancestors = test_index.ancestors(enclosing);
+            let names = ancestors
+                .iter()
+                .chain([&enclosing])
+                .filter_map(|entry| entry.name());
+
+            for name in names {
+                node_id.push_str("::");
+                node_id.push_str(name);
+            }
+        }
+
+        node_id
+    }
+}
+
+impl Default for PythonPytest {
+    fn default() -> Self {
+        Self::new(Config::default().python_pytest.clone())
+    }
+}
+
+impl TestAdapter for PythonPytest {
+    fn selector_matches(&self, selector: &TestSelector) -> SelectorMatch {
+        match selector {
+            PathWithLineNumber { path, .. } | PathOnly { path } => {
+                exclusive_or_none(uses_pytest() && path.matches(&self.config.file_patterns))
+            }
+            NameOnly { .. } => shared_or_none(uses_pytest()),
+        }
+    }
+
+    fn collect_commands(&self, selectors: &[&TestSelector]) -> Option<Vec<Cow<'_, str>>> {
+        let mut args = vec![];
+        let mut commands: Vec<Cow<str>> = vec![];
+
+        for selector in selectors {
+            match selector {
+                PathWithLineNumber { path, line } => args.push(self.find_node_id(path, *line)),
+                PathOnly { path } => args.push(path.to_string_lossy().into()),
+                NameOnly { name } => {
+                    let name = shell_words::quote(name);
+                    commands.push(format!("{} -k {}", pytest_command(), name).into());
+                }
+            };
+        }
+
+        if !args.is_empty() {
+            commands.push(format!("{} {}", pytest_command(), shell_words::join(args)).into());
+        }
+
+        none_if_empty!(commands)
+    }
+}
+
+/// Returns true if the project has any of the files pytest reads its
+/// configuration from.
+pub(super) fn uses_pytest() -> bool {
+    file_exists("pytest.ini")
+        || file_exists("conftest.py")
+        || is_in_file("pyproject.toml", "[tool.pytest")
+        || is_in_file("tox.ini", "[pytest]")
+        || is_in_file("setup.cfg", "[tool:pytest]")
+}
+
+fn pytest_command() -> Cow<'static, str> {
+    memoize_string!(format!("{}pytest", python_runner_prefix()))
+}
+
+/// Returns the prefix needed to run a command in the project's virtualenv,
+/// based on the lockfile of its package manager.
+pub(super) fn python_runner_prefix() -> &'static str {
+    if file_exists("poetry.lock") {
+        "poetry run "
+    } else if file_exists("uv.lock") {
+        "uv run "
+    } else if file_exists("Pipfile.lock") {
+        "pipenv run "
+    } else {
+        ""
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_env;
+
+    const TEST_CONTENT: &str = r#"import pytest
+
+
+def helper():
+    return 1
+
+
+def test_top_level():
+    assert helper() == 1
+
+
+class TestFoo:
+    def test_bar(self):
+        assert True
+
+    class TestNested:
+        async def test_baz(self):
+            assert True
+"#;
+
+    #[test]
+    fn selector_matches_with_pytest_markers() {
+        for (file, content) in [
+            ("pytest.ini", ""),
+            ("conftest.py", ""),
+            ("pyproject.toml", "[tool.pytest.ini_options]\n"),
+            ("tox.ini", "[pytest]\n"),
+            ("setup.cfg", "[tool:pytest]\n"),
+        ] {
+            test_env::with(|env| {
+                env.write_file("tests/test_foo.py", TEST_CONTENT);
+                let adapter = PythonPytest::default();
+                let selector = env.selector("tests/test_foo.py:9");
+
+                assert_eq!(SelectorMatch::None, adapter.selector_matches(&selector));
+
+                env.write_file(file, content);
+
+                assert_eq!(
+                    SelectorMatch::Exclusive,
+                    adapter.selector_matches(&selector)
+                );
+                assert_eq!(
+                    SelectorMatch::Shared,
+                    adapter.selector_matches(&env.selector("test_bar"))
+                );
+            })
+        }
+    }
+
+    #[test]
+    fn selector_does_not_match_other_python_files() {
+        test_env::with(|env| {
+            env.write_file("pytest.ini", "");
+            env.write_file("app/models.py", "");
+            let adapter = PythonPytest::default();
+
+            assert_eq!(
+                SelectorMatch::None,
+                adapter.selector_matches(&env.selector("app/models.py"))
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_path_with_line_number() {
+        test_env::with(|env| {
+            env.write_file("tests/test_foo.py", TEST_CONTENT);
+
+            assert_eq!(
+                env.commands(&PythonPytest::default(), &["tests/test_foo.py:9"]),
+                vec!["pytest tests/test_foo.py::test_top_level"]
+            );
+            assert_eq!(
+                env.commands(&PythonPytest::default(), &["tests/test_foo.py:14"]),
+                vec!["pytest tests/test_foo.py::TestFoo::test_bar"]
+            );
+            assert_eq!(
+                env.commands(&PythonPytest::default(), &["tests/test_foo.py:18"]),
+                vec!["pytest tests/test_foo.py::TestFoo::TestNested::test_baz"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_path_with_line_number_outside_of_tests() {
+        test_env::with(|env| {
+            env.write_file("tests/test_foo.py", TEST_CONTENT);
+
+            assert_eq!(
+                env.commands(&PythonPytest::default(), &["tests/test_foo.py:15"]),
+                vec!["pytest tests/test_foo.py::TestFoo"]
+            );
+            assert_eq!(
+                env.commands(&PythonPytest::default(), &["tests/test_foo.py:5"]),
+                vec!["pytest tests/test_foo.py"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_batches_paths() {
+        test_env::with(|env| {
+            env.write_file("tests/test_foo.py", TEST_CONTENT);
+            env.write_file("tests/test_bar.py", "");
+
+            assert_eq!(
+                env.commands(
+                    &PythonPytest::default(),
+                    &["tests/test_foo.py:9", "tests/test_bar.py", "some name"]
+                ),
+                vec![
+                    "pytest -k 'some name'",
+                    "pytest tests/test_foo.py::test_top_level tests/test_bar.py"
+                ]
+            );
+        })
+    }
+
+    #[test]
+    fn pytest_command_detects_runners() {
+        for (lockfile, expected) in [
+            ("poetry.lock", "poetry run pytest"),
+            ("uv.lock", "uv run pytest"),
+            ("Pipfile.lock", "pipenv run pytest"),
+        ] {
+            test_env::with(|env| {
+                env.write_file(lockfile, "");
+                assert_eq!(expected, pytest_command());
+            })
+        }
+
+        test_env::with(|_| {
+            assert_eq!("pytest", pytest_command());
+        })
+    }
+}
@@ -0,0 +1,265 @@
+use super::{
+    python_pytest::{python_runner_prefix, uses_pytest},
+    SelectorMatch, TestAdapter,
+};
+use crate::{
+    config::{AdapterConfig, Config},
+    memoize_string, none_if_empty,
+    selector_match::{exclusive_or_none, shared_or_none},
+    test_file::TestFile,
+    test_index::TestIndex,
+    utils::file_exists,
+    TestSelector,
+};
+use std::{borrow::Cow, path::Path};
+use TestSelector::*;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct PythonUnittest {
+    config: AdapterConfig,
+}
+
+impl PythonUnittest {
+    /// Creates a new PythonUnittest adapter.
+    pub const fn new(config: AdapterConfig) -> Self {
+        Self { config }
+    }
+
+    /// Finds the dotted label of the test method or class enclosing the given
+    /// line, falling back to the module itself. unittest only loads the
+    /// methods of `TestCase` classes, so a module-level test function is run
+    /// with the rest of its module.
+    fn find_test_label(&self, path: &TestFile, line: u32) -> String {
+        let mut label = module_label(path);
+
+        let test_index = match TestIndex::build(path, &self.config) {
+            Ok(test_index) => test_index,
+            Err(_) => return label,
+        };
+
+        if let Some(enclosing) = test_index.enclosing(line) {
+            let ancestors = test_index.ancestors(enclosing);
+
+            if enclosing.is_test() && ancestors.iter().all(|entry| entry.is_test()) {
+                return label;
+            }
+
+            let names = ancestors
+                .iter()
+                .chain([&enclosing])
+                .filter_map(|entry| entry.name());
+
+            for name in names {
+                label.push('.');
+                label.push_str(name);
+            }
+        }
+
+        label
+    }
+}
+
+impl Default for PythonUnittest {
+    fn default() -> Self {
+        Self::new(Config::default().python_unittest.clone())
+    }
+}
+
+impl TestAdapter for PythonUnittest {
+    fn selector_matches(&self, selector: &TestSelector) -> SelectorMatch {
+        match selector {
+            PathWithLineNumber { path, .. } | PathOnly { path } => {
+                exclusive_or_none(!uses_pytest() && path.matches(&self.config.file_patterns))
+            }
+            NameOnly { .. } => shared_or_none(!uses_pytest() && is_python_project()),
+        }
+    }
+
+    fn collect_commands(&self, selectors: &[&TestSelector]) -> Option<Vec<Cow<'_, str>>> {
+        let mut args = vec![];
+        let mut commands: Vec<Cow<str>> = vec![];
+
+        for selector in selectors {
+            match selector {
+                PathWithLineNumber { path, line } => args.push(self.find_test_label(path, *line)),
+                PathOnly { path } => args.push(module_label(path)),
+                NameOnly { name } => {
+                    let name = shell_words::quote(name);
+                    commands.push(format!("{} -k {}", unittest_command(), name).into());
+                }
+            };
+        }
+
+        if !args.is_empty() {
+            commands.push(format!("{} {}", unittest_command(), shell_words::join(args)).into());
+        }
+
+        none_if_empty!(commands)
+    }
+}
+
+/// Returns true if the current directory looks like the root of a Python
+/// project.
+fn is_python_project() -> bool {
+    [
+        "manage.py",
+        "setup.py",
+        "setup.cfg",
+        "pyproject.toml",
+        "requirements.txt",
+        "Pipfile",
+    ]
+    .into_iter()
+    .any(file_exists)
+}
+
+/// Converts the path of a test file to the dotted name of its module.
+fn module_label(path: &TestFile) -> String {
+    let path: &Path = path.as_ref();
+    let path = path.strip_prefix(".").unwrap_or(path).with_extension("");
+
+    path.iter()
+        .map(|segment| segment.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn unittest_command() -> Cow<'static, str> {
+    memoize_string!({
+        let prefix = python_runner_prefix();
+
+        if file_exists("manage.py") {
+            format!("{prefix}python manage.py test")
+        } else {
+            format!("{prefix}python -m unittest")
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_env;
+
+    const TEST_CONTENT: &str = r#"import unittest
+
+
+class FooTest(unittest.TestCase):
+    def setUp(self):
+        self.value = 1
+
+    def test_bar(self):
+        self.assertEqual(self.value, 1)
+
+    def test_baz(self):
+        self.assertTrue(True)
+"#;
+
+    #[test]
+    fn selector_matches_test_modules() {
+        test_env::with(|env| {
+            env.write_file("requirements.txt", "");
+            env.write_file("pkg/tests/test_foo.py", TEST_CONTENT);
+            let adapter = PythonUnittest::default();
+
+            assert_eq!(
+                SelectorMatch::Exclusive,
+                adapter.selector_matches(&env.selector("pkg/tests/test_foo.py:9"))
+            );
+            assert_eq!(
+                SelectorMatch::Shared,
+                adapter.selector_matches(&env.selector("test_bar"))
+            );
+        })
+    }
+
+    #[test]
+    fn selector_does_not_match_pytest_projects() {
+        test_env::with(|env| {
+            env.write_file("pyproject.toml", "[tool.pytest.ini_options]\n");
+            env.write_file("pkg/tests/test_foo.py", TEST_CONTENT);
+            let adapter = PythonUnittest::default();
+
+            assert_eq!(
+                SelectorMatch::None,
+                adapter.selector_matches(&env.selector("pkg/tests/test_foo.py:9"))
+            );
+            assert_eq!(
+                SelectorMatch::None,
+                adapter.selector_matches(&env.selector("test_bar"))
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_path_with_line_number() {
+        test_env::with(|env| {
+            env.write_file("pkg/tests/test_foo.py", TEST_CONTENT);
+
+            assert_eq!(
+                env.commands(&PythonUnittest::default(), &["pkg/tests/test_foo.py:9"]),
+                vec!["python -m unittest pkg.tests.test_foo.FooTest.test_bar"]
+            );
+            assert_eq!(
+                env.commands(&PythonUnittest::default(), &["pkg/tests/test_foo.py:6"]),
+                vec!["python -m unittest pkg.tests.test_foo.FooTest"]
+            );
+            assert_eq!(
+                env.commands(&PythonUnittest::default(), &["pkg/tests/test_foo.py:1"]),
+                vec!["python -m unittest pkg.tests.test_foo"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_runs_the_module_of_test_functions() {
+        test_env::with(|env| {
+            env.write_file(
+                "tests/test_foo.py",
+                "def test_foo():\n    assert True\n\n\nclass FooTest(unittest.TestCase):\n    pass\n",
+            );
+
+            assert_eq!(
+                env.commands(&PythonUnittest::default(), &["tests/test_foo.py:2"]),
+                vec!["python -m unittest tests.test_foo"]
+            );
+            assert_eq!(
+                env.commands(&PythonUnittest::default(), &["tests/test_foo.py:6"]),
+                vec!["python -m unittest tests.test_foo.FooTest"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_batches_labels() {
+        test_env::with(|env| {
+            env.write_file("pkg/tests/test_foo.py", TEST_CONTENT);
+            env.write_file("pkg/tests/test_bar.py", "");
+
+            assert_eq!(
+                env.commands(
+                    &PythonUnittest::default(),
+                    &["pkg/tests/test_foo.py:12", "pkg/tests/test_bar.py", "baz"]
+                ),
+                vec![
+                    "python -m unittest -k baz",
+                    "python -m unittest pkg.tests.test_foo.FooTest.test_baz pkg.tests.test_bar"
+                ]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_uses_django_test_runner() {
+        test_env::with(|env| {
+            env.write_file("manage.py", "");
+            env.write_file("poetry.lock", "");
+            env.write_file("app/tests.py", TEST_CONTENT);
+
+            assert_eq!(
+                env.commands(&PythonUnittest::default(), &["app/tests.py:9"]),
+                vec!["poetry run python manage.py test app.tests.FooTest.test_bar"]
+            );
+        })
+    }
+}
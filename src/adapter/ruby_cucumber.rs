@@ -0,0 +1,246 @@
+use super::{SelectorMatch, TestAdapter};
+use crate::{
+    config::{AdapterConfig, Config},
+    memoize_string, none_if_empty,
+    selector_match::{exclusive_or_none, shared_or_none},
+    test_file::TestFile,
+    test_index::TestIndex,
+    utils::{file_exists, is_in_file},
+    TestSelector,
+};
+use std::{borrow::Cow, fs::read_to_string};
+use TestSelector::*;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RubyCucumber {
+    config: AdapterConfig,
+}
+
+impl RubyCucumber {
+    /// Creates a new RubyCucumber adapter.
+    pub const fn new(config: AdapterConfig) -> Self {
+        Self { config }
+    }
+
+    /// Finds the line Cucumber should run for the given line: an examples row
+    /// of a Scenario Outline runs that example, and any other line runs the
+    /// enclosing scenario. Returns `None` if the whole file should be run.
+    fn find_scenario_line(&self, path: &TestFile, line: u32) -> Option<u32> {
+        let test_index = TestIndex::build(path, &self.config).ok()?;
+        let scenario = test_index
+            .enclosing(line)
+            .filter(|enclosing| enclosing.is_test())?;
+
+        let content = read_to_string(path).ok()?;
+        let lines = content.lines().collect::<Vec<_>>();
+
+        if is_examples_row(&lines, (line as usize).saturating_sub(1)) {
+            Some(line)
+        } else {
+            Some(scenario.line_number())
+        }
+    }
+}
+
+impl Default for RubyCucumber {
+    fn default() -> Self {
+        Self::new(Config::default().ruby_cucumber.clone())
+    }
+}
+
+impl TestAdapter for RubyCucumber {
+    fn selector_matches(&self, selector: &TestSelector) -> SelectorMatch {
+        match selector {
+            PathWithLineNumber { path, .. } | PathOnly { path } => {
+                exclusive_or_none(uses_cucumber() && path.matches(&self.config.file_patterns))
+            }
+            NameOnly { .. } => shared_or_none(uses_cucumber()),
+        }
+    }
+
+    fn collect_commands(&self, selectors: &[&TestSelector]) -> Option<Vec<Cow<'_, str>>> {
+        let mut args = vec![];
+        let mut commands: Vec<Cow<str>> = vec![];
+
+        for selector in selectors {
+            match selector {
+                PathWithLineNumber { path, line } => match self.find_scenario_line(path, *line) {
+                    Some(line) => args.push(format!("{}:{}", path.to_string_lossy(), line)),
+                    None => args.push(path.to_string_lossy().into()),
+                },
+                PathOnly { path } => args.push(path.to_string_lossy().into()),
+                NameOnly { name } => {
+                    let name = shell_words::quote(name);
+                    commands.push(format!("{} --name {}", cucumber_command(), name).into());
+                }
+            };
+        }
+
+        if !args.is_empty() {
+            commands.push(format!("{} {}", cucumber_command(), shell_words::join(args)).into());
+        }
+
+        none_if_empty!(commands)
+    }
+}
+
+/// Returns true if the project runs its features with Cucumber.
+fn uses_cucumber() -> bool {
+    file_exists("bin/cucumber") || is_in_file("Gemfile", "cucumber")
+}
+
+fn cucumber_command() -> Cow<'static, str> {
+    memoize_string!({
+        if file_exists("bin/cucumber") {
+            "bin/cucumber"
+        } else if is_in_file("Gemfile", "cucumber") {
+            "bundle exec cucumber"
+        } else {
+            "cucumber"
+        }
+    })
+}
+
+/// Returns true if the line at the given index is a row of an `Examples`
+/// table, below its header row.
+fn is_examples_row(lines: &[&str], index: usize) -> bool {
+    let is_row = |index: usize| lines.get(index).is_some_and(|l| l.trim().starts_with('|'));
+
+    if !is_row(index) {
+        return false;
+    }
+
+    let mut first_row = index;
+    while first_row > 0 && is_row(first_row - 1) {
+        first_row -= 1;
+    }
+
+    let table_keyword = lines[..first_row]
+        .iter()
+        .rev()
+        .map(|line| line.trim())
+        .find(|line| !line.is_empty() && !line.starts_with('#'));
+
+    first_row < index
+        && table_keyword.is_some_and(|keyword| {
+            keyword.starts_with("Examples:") || keyword.starts_with("Scenarios:")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_env;
+
+    const FEATURE_CONTENT: &str = r#"Feature: Eating cucumbers
+
+  Background:
+    Given a basket
+
+  Scenario: Eating one cucumber
+    Given there are 5 cucumbers
+    When I eat 1 cucumber
+    Then I should have 4 cucumbers
+
+  Scenario Outline: Eating many cucumbers
+    Given there are <start> cucumbers
+    When I eat <eat> cucumbers
+      | note  |
+      | tasty |
+
+    Examples:
+      | start | eat |
+      | 12    | 5   |
+      | 20    | 5   |
+"#;
+
+    #[test]
+    fn selector_matches_feature_files() {
+        test_env::with(|env| {
+            env.write_file("features/eating.feature", FEATURE_CONTENT);
+            let adapter = RubyCucumber::default();
+            let selector = env.selector("features/eating.feature:8");
+
+            assert_eq!(SelectorMatch::None, adapter.selector_matches(&selector));
+
+            env.write_file("Gemfile", "gem 'cucumber'");
+
+            assert_eq!(
+                SelectorMatch::Exclusive,
+                adapter.selector_matches(&selector)
+            );
+            assert_eq!(
+                SelectorMatch::Shared,
+                adapter.selector_matches(&env.selector("Eating one cucumber"))
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_path_with_line_number() {
+        test_env::with(|env| {
+            env.write_file("features/eating.feature", FEATURE_CONTENT);
+
+            assert_eq!(
+                env.commands(&RubyCucumber::default(), &["features/eating.feature:8"]),
+                vec!["cucumber features/eating.feature:6"]
+            );
+            assert_eq!(
+                env.commands(&RubyCucumber::default(), &["features/eating.feature:4"]),
+                vec!["cucumber features/eating.feature"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_path_with_line_number_in_scenario_outline() {
+        test_env::with(|env| {
+            env.write_file("features/eating.feature", FEATURE_CONTENT);
+
+            assert_eq!(
+                env.commands(&RubyCucumber::default(), &["features/eating.feature:19"]),
+                vec!["cucumber features/eating.feature:19"]
+            );
+            assert_eq!(
+                env.commands(&RubyCucumber::default(), &["features/eating.feature:18"]),
+                vec!["cucumber features/eating.feature:11"]
+            );
+            assert_eq!(
+                env.commands(&RubyCucumber::default(), &["features/eating.feature:15"]),
+                vec!["cucumber features/eating.feature:11"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_batches_paths() {
+        test_env::with(|env| {
+            env.write_file("bin/cucumber", "");
+            env.write_file("features/eating.feature", FEATURE_CONTENT);
+            env.write_file("features/buying.feature", "");
+
+            assert_eq!(
+                env.commands(
+                    &RubyCucumber::default(),
+                    &[
+                        "features/eating.feature:20",
+                        "features/buying.feature",
+                        "Eating one cucumber"
+                    ]
+                ),
+                vec![
+                    "bin/cucumber --name 'Eating one cucumber'",
+                    "bin/cucumber features/eating.feature:20 features/buying.feature"
+                ]
+            );
+        })
+    }
+
+    #[test]
+    fn cucumber_command_detects_gemfile() {
+        test_env::with(|env| {
+            env.write_file("Gemfile", "gem 'cucumber'");
+            assert_eq!("bundle exec cucumber", cucumber_command());
+        })
+    }
+}
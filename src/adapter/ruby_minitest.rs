@@ -1,56 +1,101 @@
 use super::{SelectorMatch, TestAdapter};
 use crate::{
+    config::{AdapterConfig, Config},
     memoize_string, none_if_empty,
     selector_match::{exclusive_or_none, shared_or_none},
     test_file::TestFile,
     test_index::TestIndex,
-    utils::is_in_file,
+    utils::{file_exists, is_in_file},
     TestSelector,
 };
+use lazy_static::lazy_static;
+use regex::Regex;
 use std::borrow::Cow;
 use TestSelector::*;
 
-const TEST_PATTERNS: &[&str] = &["  def test_", "  it \"", "  it '", "  test \"", "  test '"];
-
 #[derive(Debug, PartialEq, Eq)]
-pub struct RubyMinitest {}
+pub struct RubyMinitest {
+    config: AdapterConfig,
+}
 
 impl RubyMinitest {
     /// Creates a new RubyMinitest adapter.
-    #[allow(dead_code)]
-    pub const fn new() -> Self {
-        Self {}
+    pub const fn new(config: AdapterConfig) -> Self {
+        Self { config }
+    }
+
+    /// Finds the `--name` filter for the test or namespace enclosing the given
+    /// line. Returns `None` if the whole file should be run.
+    fn find_test_pattern(&self, path: &TestFile, input_line_no: u32) -> Option<String> {
+        let test_index = TestIndex::build(path, &self.config).ok()?;
+        let enclosing = test_index.enclosing(input_line_no)?;
+
+        let mut namespaces = test_index
+            .ancestors(enclosing)
+            .iter()
+            .filter_map(|namespace| namespace.name())
+            .collect::<Vec<_>>();
+
+        if enclosing.is_test() {
+            let name = enclosing
+                .name()
+                .unwrap_or_else(|| enclosing.content().trim());
+
+            Some(format_test_name(&namespaces, enclosing.content(), name))
+        } else {
+            namespaces.extend(enclosing.name());
+
+            Some(format_namespace_name(&namespaces))
+        }
+    }
+}
+
+impl Default for RubyMinitest {
+    fn default() -> Self {
+        Self::new(Config::default().ruby_minitest.clone())
     }
 }
 
 impl TestAdapter for RubyMinitest {
     fn selector_matches(&self, selector: &TestSelector) -> SelectorMatch {
         match selector {
-            PathWithLineNumber { path, .. } => {
-                exclusive_or_none(is_in_file("Gemfile", "minitest") && path.matches(".rb"))
+            PathWithLineNumber { path, .. } | PathOnly { path } => {
+                exclusive_or_none(uses_minitest() && path.matches(&self.config.file_patterns))
             }
-            PathOnly { path } => {
-                exclusive_or_none(is_in_file("Gemfile", "minitest") && path.matches(".rb"))
-            }
-            NameOnly { .. } => shared_or_none(is_in_file("Gemfile", "minitest")),
+            NameOnly { .. } => shared_or_none(uses_minitest()),
         }
     }
 
     fn collect_commands(&self, selectors: &[&TestSelector]) -> Option<Vec<Cow<'_, str>>> {
+        if uses_rails() {
+            return rails_commands(selectors);
+        }
+
         let mut commands: Vec<Cow<str>> = vec![];
         let cmd = minitest_command();
 
         for selector in selectors {
             match selector {
                 PathWithLineNumber { path, line } => {
-                    if let Some(pattern) = find_test_pattern(path, *line) {
-                        let path = path.to_string_lossy();
+                    let pattern = self.find_test_pattern(path, *line);
+                    let path = shell_words::quote(&path.to_string_lossy()).to_string();
 
-                        commands.push(format!("{cmd} {path} --name='{pattern}'").into());
+                    match pattern {
+                        Some(pattern) => {
+                            let pattern = shell_words::quote(&pattern);
+                            commands.push(format!("{cmd} {path} --name={pattern}").into());
+                        }
+                        None => commands.push(format!("{cmd} {path}").into()),
                     }
                 }
-                PathOnly { path } => commands.push(format!("{cmd} {path}").into()),
-                NameOnly { name } => commands.push(format!("{cmd} --name=/{}/", name).into()),
+                PathOnly { path } => {
+                    let path = shell_words::quote(&path.to_string_lossy()).to_string();
+                    commands.push(format!("{cmd} {path}").into());
+                }
+                NameOnly { name } => {
+                    let name = shell_words::quote(&format!("/{name}/")).to_string();
+                    commands.push(format!("{cmd} --name={name}").into());
+                }
             };
         }
 
@@ -58,25 +103,76 @@ impl TestAdapter for RubyMinitest {
     }
 }
 
-fn find_test_pattern(path: &TestFile, input_line_no: u32) -> Option<String> {
-    let test_index = TestIndex::build(path, TEST_PATTERNS).ok()?;
-    let closest_match = test_index.closest_to_line_number(input_line_no)?;
+/// Formats a captured test name as a `--name` filter, qualified by the
+/// enclosing namespaces. Method names match `Class#test_name` exactly, while
+/// descriptions match the method generated for them by the keyword declaring
+/// the test: ActiveSupport's `test` defines `Class#test_the_name`, and
+/// Minitest::Spec's `it` defines `Class#test_0001_the name`.
+fn format_test_name(namespaces: &[&str], content: &str, name: &str) -> String {
+    lazy_static! {
+        static ref WHITESPACE: Regex = Regex::new(r"\s+").unwrap();
+    }
 
-    Some(format_line_match(closest_match.content()))
-}
+    let class_name = namespaces.join("::");
+    let keyword = content
+        .trim_start()
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .next();
 
-fn format_line_match(line: &str) -> String {
-    let line = line.trim();
+    let method_name = match keyword {
+        Some("def") if class_name.is_empty() => return name.to_string(),
+        Some("def") => return format!("{class_name}#{name}"),
+        Some("test") => format!("test_{}", regex::escape(&WHITESPACE.replace_all(name, "_"))),
+        _ => format!("test_\\d+_{}", regex::escape(name)),
+    };
 
-    if line.starts_with("def test_") {
-        line.trim_start_matches("def ").to_string()
+    if class_name.is_empty() {
+        format!("/#{method_name}$/")
     } else {
-        let line = TEST_PATTERNS
-            .iter()
-            .fold(line, |acc, patt| acc.trim_start_matches(patt.trim()));
+        format!("/^{}#{method_name}$/", regex::escape(&class_name))
+    }
+}
+
+/// Formats the names of nested namespaces as a `--name` filter matching all of
+/// the tests within them.
+fn format_namespace_name(namespaces: &[&str]) -> String {
+    format!("/^{}(::|#)/", regex::escape(&namespaces.join("::")))
+}
+
+/// Builds the `bin/rails test` commands, which load the Rails environment and
+/// resolve line numbers themselves, so paths are batched into one command.
+fn rails_commands<'a>(selectors: &[&TestSelector]) -> Option<Vec<Cow<'a, str>>> {
+    let mut args = vec![];
+    let mut commands: Vec<Cow<str>> = vec![];
+
+    for selector in selectors {
+        match selector {
+            PathWithLineNumber { path, line } => {
+                args.push(format!("{}:{}", path.to_string_lossy(), line))
+            }
+            PathOnly { path } => args.push(path.to_string_lossy().into()),
+            NameOnly { name } => {
+                let name = shell_words::quote(&format!("/{name}/")).to_string();
+                commands.push(format!("bin/rails test --name={name}").into());
+            }
+        };
+    }
 
-        format!("/_{}$/", line.trim_end_matches("\" do"))
+    if !args.is_empty() {
+        commands.push(format!("bin/rails test {}", shell_words::join(args)).into());
     }
+
+    none_if_empty!(commands)
+}
+
+/// Returns true if the project uses Minitest, which Rails apps do by default.
+fn uses_minitest() -> bool {
+    is_in_file("Gemfile", "minitest") || uses_rails()
+}
+
+/// Returns true if the project is a Rails app, with its own test runner.
+fn uses_rails() -> bool {
+    file_exists("bin/rails") && file_exists("config/application.rb")
 }
 
 fn minitest_command() -> Cow<'static, str> {
@@ -91,7 +187,7 @@ fn minitest_command() -> Cow<'static, str> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{adapter::TestAdapter, test_env};
+    use crate::{adapter::TestAdapter, selector_match::SelectorMatch, test_env};
 
     use super::RubyMinitest;
 
@@ -112,12 +208,43 @@ mod tests {
 
             env.write_file("my_test.rb", test_content);
             let selectors = [&env.selector("my_test.rb:4")];
-            let adapter = RubyMinitest::new();
+            let adapter = RubyMinitest::default();
             let commands = adapter.collect_commands(selectors.as_slice()).unwrap();
 
             assert_eq!(
                 commands,
-                vec!["ruby -rminitest/autorun -Ilib:test my_test.rb --name='test_something_else'"]
+                vec![
+                    "ruby -rminitest/autorun -Ilib:test my_test.rb --name='MyTest#test_something'"
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn quotes_test_names_with_apostrophes() {
+        test_env::with(|env| {
+            let test_content = r#"
+              describe "FooTest" do
+                it "doesn't fail" do
+                  assert true
+                end
+              end
+            "#;
+
+            env.write_file("my_test.rb", test_content);
+            let selectors = [&env.selector("my_test.rb:4")];
+            let adapter = RubyMinitest::default();
+            let commands = adapter.collect_commands(selectors.as_slice()).unwrap();
+
+            assert_eq!(
+                commands,
+                vec![
+                    r"ruby -rminitest/autorun -Ilib:test my_test.rb --name='/^FooTest#test_\d+_doesn'\''t fail$/'"
+                ]
+            );
+            assert_eq!(
+                shell_words::split(&commands[0]).unwrap()[4],
+                r"--name=/^FooTest#test_\d+_doesn't fail$/"
             );
         });
     }
@@ -139,12 +266,176 @@ mod tests {
 
             env.write_file("my_test.rb", test_content);
             let selectors = [&env.selector("my_test.rb:4")];
-            let adapter = RubyMinitest::new();
+            let adapter = RubyMinitest::default();
+            let commands = adapter.collect_commands(selectors.as_slice()).unwrap();
+
+            assert_eq!(
+                commands,
+                vec![
+                    r"ruby -rminitest/autorun -Ilib:test my_test.rb --name='/^MyTest#test_\d+_does something$/'"
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn generates_commands_for_path_with_line_number_using_test_syntax() {
+        test_env::with(|env| {
+            let test_content = r#"
+              class MyTest < ActiveSupport::TestCase
+                  test("does something (else)?") do
+                    assert true
+                  end
+              end
+            "#;
+
+            env.write_file("my_test.rb", test_content);
+            let selectors = [&env.selector("my_test.rb:4")];
+            let adapter = RubyMinitest::default();
+            let commands = adapter.collect_commands(selectors.as_slice()).unwrap();
+
+            assert_eq!(
+                commands,
+                vec![
+                    r"ruby -rminitest/autorun -Ilib:test my_test.rb --name='/^MyTest#test_does_something_\(else\)\?$/'"
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn generates_commands_for_nested_namespaces() {
+        test_env::with(|env| {
+            let test_content = r#"
+              module Foo
+                class BarTest < Minitest::Test
+                  def test_baz
+                    assert true
+                  end
+                end
+              end
+            "#;
+
+            env.write_file("my_test.rb", test_content);
+            let selectors = [&env.selector("my_test.rb:5")];
+            let adapter = RubyMinitest::default();
+            let commands = adapter.collect_commands(selectors.as_slice()).unwrap();
+
+            assert_eq!(
+                commands,
+                vec![
+                    "ruby -rminitest/autorun -Ilib:test my_test.rb --name='Foo::BarTest#test_baz'"
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn generates_commands_for_nested_describes() {
+        test_env::with(|env| {
+            let test_content = r#"
+              describe Foo do
+                describe "bar" do
+                  it "does baz" do
+                    assert true
+                  end
+                end
+              end
+            "#;
+
+            env.write_file("my_test.rb", test_content);
+            let selectors = [&env.selector("my_test.rb:5")];
+            let adapter = RubyMinitest::default();
+            let commands = adapter.collect_commands(selectors.as_slice()).unwrap();
+
+            assert_eq!(
+                commands,
+                vec![
+                    r"ruby -rminitest/autorun -Ilib:test my_test.rb --name='/^Foo::bar#test_\d+_does baz$/'"
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn generates_commands_for_lines_between_tests() {
+        test_env::with(|env| {
+            let test_content = r#"
+              class MyTest < Minitest::Test
+                def test_something
+                  assert true
+                end
+
+                def test_something_else
+                  assert true
+                end
+              end
+            "#;
+
+            env.write_file("my_test.rb", test_content);
+            let selectors = [&env.selector("my_test.rb:6")];
+            let adapter = RubyMinitest::default();
+            let commands = adapter.collect_commands(selectors.as_slice()).unwrap();
+
+            assert_eq!(
+                commands,
+                vec!["ruby -rminitest/autorun -Ilib:test my_test.rb --name='/^MyTest(::|#)/'"]
+            );
+        });
+    }
+
+    #[test]
+    fn generates_commands_for_lines_outside_of_tests() {
+        test_env::with(|env| {
+            let test_content = r#"
+              require "minitest/autorun"
+
+              class MyTest < Minitest::Test
+                def test_something
+                  assert true
+                end
+              end
+            "#;
+
+            env.write_file("my_test.rb", test_content);
+            let selectors = [&env.selector("my_test.rb:2")];
+            let adapter = RubyMinitest::default();
+            let commands = adapter.collect_commands(selectors.as_slice()).unwrap();
+
+            assert_eq!(
+                commands,
+                vec!["ruby -rminitest/autorun -Ilib:test my_test.rb"]
+            );
+        });
+    }
+
+    #[test]
+    fn generates_batched_commands_for_rails_apps() {
+        test_env::with(|env| {
+            env.write_file("bin/rails", "");
+            env.write_file("config/application.rb", "");
+            env.write_file("test/models/user_test.rb", "");
+            env.write_file("test/models/post_test.rb", "");
+            let selectors = [
+                &env.selector("test/models/user_test.rb:12"),
+                &env.selector("test/models/post_test.rb"),
+                &env.selector("creates users"),
+            ];
+            let adapter = RubyMinitest::default();
+
+            assert_eq!(
+                SelectorMatch::Exclusive,
+                adapter.selector_matches(selectors[0])
+            );
+
             let commands = adapter.collect_commands(selectors.as_slice()).unwrap();
 
             assert_eq!(
                 commands,
-                vec!["ruby -rminitest/autorun -Ilib:test my_test.rb --name='/_does something else$/'"]
+                vec![
+                    "bin/rails test --name='/creates users/'",
+                    "bin/rails test test/models/user_test.rb:12 test/models/post_test.rb"
+                ]
             );
         });
     }
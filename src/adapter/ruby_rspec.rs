@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 
 use crate::{
+    config::{AdapterConfig, Config},
     memoize_string,
     selector_match::{exclusive_or_none, shared_or_none, SelectorMatch},
     utils::{file_exists, is_in_file},
@@ -10,13 +11,20 @@ use crate::{
 use super::TestAdapter;
 
 #[derive(Debug, PartialEq, Eq)]
-pub struct RubyRspec {}
+pub struct RubyRspec {
+    config: AdapterConfig,
+}
 
 impl RubyRspec {
-    /// Creates a new RubyMinitest adapter.
-    #[allow(dead_code)]
-    pub const fn new() -> Self {
-        Self {}
+    /// Creates a new RubyRspec adapter.
+    pub const fn new(config: AdapterConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Default for RubyRspec {
+    fn default() -> Self {
+        Self::new(Config::default().ruby_rspec.clone())
     }
 }
 
@@ -24,9 +32,11 @@ impl TestAdapter for RubyRspec {
     fn selector_matches(&self, selector: &TestSelector) -> SelectorMatch {
         match selector {
             TestSelector::PathWithLineNumber { path, .. } => {
-                exclusive_or_none(path.matches("_spec.rb"))
+                exclusive_or_none(path.matches(&self.config.file_patterns))
+            }
+            TestSelector::PathOnly { path } => {
+                exclusive_or_none(path.matches(&self.config.file_patterns))
             }
-            TestSelector::PathOnly { path } => exclusive_or_none(path.matches("_spec.rb")),
             TestSelector::NameOnly { .. } => shared_or_none(is_in_file("Gemfile", "rspec")),
         }
     }
@@ -102,7 +112,7 @@ mod tests {
     #[test]
     fn selector_matches_path_with_line_number() {
         test_env::with(|_| {
-            let adapter = RubyRspec::new();
+            let adapter = RubyRspec::default();
             let selector = TestSelector::PathWithLineNumber {
                 path: "foo_spec.rb".into(),
                 line: 1,
@@ -117,7 +127,7 @@ mod tests {
     #[test]
     fn selector_matches_path_only() {
         test_env::with(|_| {
-            let adapter = RubyRspec::new();
+            let adapter = RubyRspec::default();
             let selector = TestSelector::PathOnly {
                 path: "foo_spec.rb".into(),
             };
@@ -132,7 +142,7 @@ mod tests {
     fn selector_matches_name_only() {
         test_env::with(|test_env| {
             test_env.write_file("Gemfile", "gem 'rspec'");
-            let adapter = RubyRspec::new();
+            let adapter = RubyRspec::default();
             let selector = TestSelector::NameOnly { name: "foo".into() };
             assert_eq!(SelectorMatch::Shared, adapter.selector_matches(&selector));
         })
@@ -141,7 +151,7 @@ mod tests {
     #[test]
     fn collect_commands_path_with_line_number() {
         test_env::with(|_| {
-            let adapter = RubyRspec::new();
+            let adapter = RubyRspec::default();
             let selector = TestSelector::PathWithLineNumber {
                 path: "foo_spec.rb".into(),
                 line: 1,
@@ -154,7 +164,7 @@ mod tests {
     #[test]
     fn collect_commands_path_only() {
         test_env::with(|_| {
-            let adapter = RubyRspec::new();
+            let adapter = RubyRspec::default();
             let selector = TestSelector::PathOnly {
                 path: "foo_spec.rb".into(),
             };
@@ -167,7 +177,7 @@ mod tests {
     fn collect_commands_name_only() {
         test_env::with(|test_env| {
             test_env.write_file("Gemfile", "gem 'rspec'");
-            let adapter = RubyRspec::new();
+            let adapter = RubyRspec::default();
             let selector = TestSelector::NameOnly { name: "foo".into() };
             let commands = adapter.collect_commands(&[&selector]).unwrap();
             assert_eq!(commands, vec!["bundle exec rspec --example foo"]);
@@ -178,7 +188,7 @@ mod tests {
     fn collect_commands_name_only_and_path_with_line_number() {
         test_env::with(|test_env| {
             test_env.write_file("Gemfile", "gem 'rspec'");
-            let adapter = RubyRspec::new();
+            let adapter = RubyRspec::default();
             let selector1 = TestSelector::NameOnly { name: "foo".into() };
             let selector2 = TestSelector::PathWithLineNumber {
                 path: "foo_spec.rb".into(),
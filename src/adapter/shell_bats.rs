@@ -0,0 +1,184 @@
+use super::{SelectorMatch, TestAdapter};
+use crate::{
+    config::{AdapterConfig, Config},
+    memoize_string, none_if_empty,
+    selector_match::{exclusive_or_none, shared_or_none},
+    test_file::TestFile,
+    test_index::TestIndex,
+    utils::file_exists,
+    TestSelector,
+};
+use std::borrow::Cow;
+use TestSelector::*;
+
+/// Where bats-core is installed within a project, as a git submodule or an npm
+/// package.
+const LOCAL_BATS: [&str; 2] = ["test/bats/bin/bats", "node_modules/.bin/bats"];
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ShellBats {
+    config: AdapterConfig,
+}
+
+impl ShellBats {
+    /// Creates a new ShellBats adapter.
+    pub const fn new(config: AdapterConfig) -> Self {
+        Self { config }
+    }
+
+    /// Finds the `--filter` for the `@test` enclosing the given line. Returns
+    /// `None` if the whole file should be run.
+    fn find_filter(&self, path: &TestFile, line: u32) -> Option<String> {
+        let test_index = TestIndex::build(path, &self.config).ok()?;
+        let test = test_index.enclosing(line)?;
+
+        test.name().map(|name| format!("^{}$", regex::escape(name)))
+    }
+}
+
+impl Default for ShellBats {
+    fn default() -> Self {
+        Self::new(Config::default().shell_bats.clone())
+    }
+}
+
+impl TestAdapter for ShellBats {
+    fn selector_matches(&self, selector: &TestSelector) -> SelectorMatch {
+        match selector {
+            PathWithLineNumber { path, .. } | PathOnly { path } => {
+                exclusive_or_none(path.matches(&self.config.file_patterns))
+            }
+            NameOnly { .. } => shared_or_none(LOCAL_BATS.into_iter().any(file_exists)),
+        }
+    }
+
+    fn collect_commands(&self, selectors: &[&TestSelector]) -> Option<Vec<Cow<'_, str>>> {
+        let mut commands: Vec<Cow<str>> = vec![];
+
+        for selector in selectors {
+            let mut args = vec![];
+
+            match selector {
+                PathWithLineNumber { path, line } => {
+                    if let Some(filter) = self.find_filter(path, *line) {
+                        args.extend(["--filter".to_string(), filter]);
+                    }
+                    args.push(path.to_string_lossy().to_string());
+                }
+                PathOnly { path } => args.push(path.to_string_lossy().to_string()),
+                NameOnly { name } => args.extend([
+                    "--recursive".to_string(),
+                    "--filter".to_string(),
+                    name.to_string(),
+                    "test".to_string(),
+                ]),
+            };
+
+            commands.push(format!("{} {}", bats_command(), shell_words::join(args)).into());
+        }
+
+        none_if_empty!(commands)
+    }
+}
+
+fn bats_command() -> Cow<'static, str> {
+    memoize_string!(LOCAL_BATS
+        .into_iter()
+        .find(|bats| file_exists(bats))
+        .unwrap_or("bats"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_env;
+
+    const TEST_CONTENT: &str = r#"#!/usr/bin/env bats
+
+setup() {
+  load 'test_helper/common'
+}
+
+@test "deploys (dry run)" {
+  run ./deploy --dry-run
+  [ "$status" -eq 0 ]
+}
+
+@test 'rolls back' {
+  run ./rollback
+}
+"#;
+
+    #[test]
+    fn selector_matches_bats_files() {
+        test_env::with(|env| {
+            env.write_file("test/deploy.bats", TEST_CONTENT);
+            env.write_file("deploy.sh", "");
+            let adapter = ShellBats::default();
+
+            assert_eq!(
+                SelectorMatch::Exclusive,
+                adapter.selector_matches(&env.selector("test/deploy.bats:8"))
+            );
+            assert_eq!(
+                SelectorMatch::None,
+                adapter.selector_matches(&env.selector("deploy.sh"))
+            );
+            assert_eq!(
+                SelectorMatch::None,
+                adapter.selector_matches(&env.selector("rolls back"))
+            );
+
+            env.write_file("test/bats/bin/bats", "");
+
+            assert_eq!(
+                SelectorMatch::Shared,
+                adapter.selector_matches(&env.selector("rolls back"))
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_path_with_line_number() {
+        test_env::with(|env| {
+            env.write_file("test/deploy.bats", TEST_CONTENT);
+
+            assert_eq!(
+                env.commands(&ShellBats::default(), &["test/deploy.bats:8"]),
+                vec![r"bats --filter '^deploys \(dry run\)$' test/deploy.bats"]
+            );
+            assert_eq!(
+                env.commands(&ShellBats::default(), &["test/deploy.bats:13"]),
+                vec!["bats --filter '^rolls back$' test/deploy.bats"]
+            );
+            assert_eq!(
+                env.commands(&ShellBats::default(), &["test/deploy.bats:4"]),
+                vec!["bats test/deploy.bats"]
+            );
+        })
+    }
+
+    #[test]
+    fn collect_commands_name_only() {
+        test_env::with(|env| {
+            assert_eq!(
+                env.commands(&ShellBats::default(), &["rolls back"]),
+                vec!["bats --recursive --filter 'rolls back' test"]
+            );
+        })
+    }
+
+    #[test]
+    fn bats_command_detects_local_installs() {
+        for bats in LOCAL_BATS {
+            test_env::with(|env| {
+                env.write_file(bats, "");
+                assert_eq!(bats, bats_command());
+            })
+        }
+
+        test_env::with(|_| {
+            assert_eq!("bats", bats_command());
+        })
+    }
+}
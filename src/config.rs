@@ -3,6 +3,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use regex::Regex;
 use serde::Deserialize;
 
 lazy_static::lazy_static! {
@@ -19,17 +20,75 @@ pub struct AdapterConfig {
     pub namespace_patterns: Vec<String>,
 }
 
+impl AdapterConfig {
+    /// Merge `other` over this config, preferring any patterns it sets.
+    pub fn merge(&self, other: &Self) -> Self {
+        fn pick(base: &[String], over: &[String]) -> Vec<String> {
+            if over.is_empty() { base } else { over }.to_vec()
+        }
+
+        Self {
+            file_patterns: pick(&self.file_patterns, &other.file_patterns),
+            test_patterns: pick(&self.test_patterns, &other.test_patterns),
+            namespace_patterns: pick(&self.namespace_patterns, &other.namespace_patterns),
+        }
+    }
+
+    /// Returns an error if any of the patterns is not a valid regex.
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        let patterns = self
+            .file_patterns
+            .iter()
+            .chain(&self.test_patterns)
+            .chain(&self.namespace_patterns);
+
+        for pattern in patterns {
+            Regex::new(pattern)?;
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Default)]
 #[serde(deny_unknown_fields, default, rename_all = "kebab-case")]
 pub struct Config {
-    ruby_rspec: AdapterConfig,
-    ruby_minitest: AdapterConfig,
-    cargo_test: AdapterConfig,
+    pub ruby_rspec: AdapterConfig,
+    pub ruby_minitest: AdapterConfig,
+    pub cargo_test: AdapterConfig,
+    pub cargo_nextest: AdapterConfig,
+    pub python_pytest: AdapterConfig,
+    pub python_unittest: AdapterConfig,
+    pub go_test: AdapterConfig,
+    pub js_jest: AdapterConfig,
+    pub js_vitest: AdapterConfig,
+    pub js_mocha: AdapterConfig,
+    pub jvm_junit: AdapterConfig,
+    pub php_unit: AdapterConfig,
+    pub elixir_ex_unit: AdapterConfig,
+    pub ruby_cucumber: AdapterConfig,
+    pub dotnet_test: AdapterConfig,
+    pub shell_bats: AdapterConfig,
+    pub lua_busted: AdapterConfig,
+    pub deno_test: AdapterConfig,
+    pub bun_test: AdapterConfig,
+    pub cpp_gtest: AdapterConfig,
+    pub cpp_catch2: AdapterConfig,
+    pub haskell_test: AdapterConfig,
+    pub dart_test: AdapterConfig,
 }
 
 impl Config {
+    /// Load the project config from the given folder, merged over the default
+    /// config.
+    pub fn load(cwd: &Path) -> Result<Self, Box<dyn Error>> {
+        match Self::from_dir(cwd)? {
+            Some(config) => Ok(Self::default().merge(&config)),
+            None => Ok(Self::default().clone()),
+        }
+    }
+
     /// Load the config from the given folder.
-    #[allow(dead_code)]
     pub fn from_dir(cwd: &std::path::Path) -> Result<Option<Self>, Box<dyn Error>> {
         let config = if let Some(path) =
             find_project_file(cwd, &["ttest.toml", "_ttest.toml", ".ttest.toml"])
@@ -50,13 +109,70 @@ impl Config {
     /// Load the config from the given TOML string.
     pub fn from_toml(data: &str) -> Result<Self, Box<dyn Error>> {
         let content: Config = toml_edit::de::from_str(data)?;
+
+        for adapter_config in [
+            &content.ruby_rspec,
+            &content.ruby_minitest,
+            &content.cargo_test,
+            &content.cargo_nextest,
+            &content.python_pytest,
+            &content.python_unittest,
+            &content.go_test,
+            &content.js_jest,
+            &content.js_vitest,
+            &content.js_mocha,
+            &content.jvm_junit,
+            &content.php_unit,
+            &content.elixir_ex_unit,
+            &content.ruby_cucumber,
+            &content.dotnet_test,
+            &content.shell_bats,
+            &content.lua_busted,
+            &content.deno_test,
+            &content.bun_test,
+            &content.cpp_gtest,
+            &content.cpp_catch2,
+            &content.haskell_test,
+            &content.dart_test,
+        ] {
+            adapter_config.validate()?;
+        }
+
         Ok(content)
     }
 
     /// Get a reference to the default config.
-    #[allow(dead_code)]
     pub fn default() -> &'static Self {
-        &*DEFAULT_CONFIG
+        &DEFAULT_CONFIG
+    }
+
+    /// Merge `other` over this config, adapter by adapter.
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            ruby_rspec: self.ruby_rspec.merge(&other.ruby_rspec),
+            ruby_minitest: self.ruby_minitest.merge(&other.ruby_minitest),
+            cargo_test: self.cargo_test.merge(&other.cargo_test),
+            cargo_nextest: self.cargo_nextest.merge(&other.cargo_nextest),
+            python_pytest: self.python_pytest.merge(&other.python_pytest),
+            python_unittest: self.python_unittest.merge(&other.python_unittest),
+            go_test: self.go_test.merge(&other.go_test),
+            js_jest: self.js_jest.merge(&other.js_jest),
+            js_vitest: self.js_vitest.merge(&other.js_vitest),
+            js_mocha: self.js_mocha.merge(&other.js_mocha),
+            jvm_junit: self.jvm_junit.merge(&other.jvm_junit),
+            php_unit: self.php_unit.merge(&other.php_unit),
+            elixir_ex_unit: self.elixir_ex_unit.merge(&other.elixir_ex_unit),
+            ruby_cucumber: self.ruby_cucumber.merge(&other.ruby_cucumber),
+            dotnet_test: self.dotnet_test.merge(&other.dotnet_test),
+            shell_bats: self.shell_bats.merge(&other.shell_bats),
+            lua_busted: self.lua_busted.merge(&other.lua_busted),
+            deno_test: self.deno_test.merge(&other.deno_test),
+            bun_test: self.bun_test.merge(&other.bun_test),
+            cpp_gtest: self.cpp_gtest.merge(&other.cpp_gtest),
+            cpp_catch2: self.cpp_catch2.merge(&other.cpp_catch2),
+            haskell_test: self.haskell_test.merge(&other.haskell_test),
+            dart_test: self.dart_test.merge(&other.dart_test),
+        }
     }
 }
 
@@ -74,6 +190,7 @@ fn find_project_file(dir: impl AsRef<Path>, names: &[&str]) -> Option<PathBuf> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_env;
     use std::path::PathBuf;
 
     #[test]
@@ -105,6 +222,39 @@ mod tests {
             "\\s*(test|it)(\\(| )(\"|')(?P<name>.*)(\"|')"
         );
 
-        assert_eq!(config.cargo_test.file_patterns, Vec::<String>::new());
+        assert_eq!(config.cargo_test.file_patterns, vec![r"\.rs$"]);
+    }
+
+    #[test]
+    fn test_from_toml_rejects_invalid_regex() {
+        let result = Config::from_toml("[ruby-rspec]\nfile-patterns = ['(unclosed']");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_load_merges_project_config_over_default() {
+        test_env::with(|env| {
+            env.write_file(
+                "ttest.toml",
+                "[ruby-rspec]\nfile-patterns = ['_check\\.rb$']",
+            );
+
+            let config = Config::load(&env.temp_dir).unwrap();
+
+            assert_eq!(config.ruby_rspec.file_patterns, vec![r"_check\.rb$"]);
+            assert_eq!(
+                config.ruby_rspec.test_patterns,
+                Config::default().ruby_rspec.test_patterns
+            );
+            assert_eq!(config.ruby_minitest, Config::default().ruby_minitest);
+        })
+    }
+
+    #[test]
+    fn test_load_without_project_config_uses_default() {
+        test_env::with(|env| {
+            let config = Config::load(&env.temp_dir).unwrap();
+            assert_eq!(&config, Config::default());
+        })
     }
 }
@@ -10,9 +10,9 @@ mod utils;
 #[cfg(test)]
 mod test_env;
 
-use std::error::Error;
+use std::{env::current_dir, error::Error};
 
-use crate::test_selector::TestSelector;
+use crate::{config::Config, test_selector::TestSelector};
 use clap::Parser;
 
 /// A CLI tool for running tests for any programming language.
@@ -28,8 +28,10 @@ struct Args {
 fn main() -> Result<(), Box<dyn Error>> {
     let args = Args::parse();
     let selectors = parse_selectors(&args)?;
+    let config = Config::load(&current_dir()?)?;
+    let adapters = adapter::all(&config);
 
-    runner::run_all(&selectors)?;
+    runner::run_all(&adapters, &selectors)?;
 
     Ok(())
 }
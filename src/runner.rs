@@ -5,7 +5,7 @@ use std::{
 };
 
 use crate::{
-    adapter::{self, TestAdapter},
+    adapter::{Adapter, TestAdapter},
     selector_match::SelectorMatch,
     test_selector::TestSelector,
 };
@@ -16,27 +16,31 @@ fn take_selectors(
     selectors: &mut Vec<TestSelector>,
     adapter: &impl TestAdapter,
 ) -> Vec<TestSelector> {
-    let result = selectors.clone();
-    let result = result.iter().enumerate();
+    let mut result = vec![];
+
+    selectors.retain(|selector| match adapter.selector_matches(selector) {
+        SelectorMatch::None => true,
+        SelectorMatch::Shared => {
+            result.push(selector.clone());
+            true
+        }
+        SelectorMatch::Exclusive => {
+            result.push(selector.clone());
+            false
+        }
+    });
 
     result
-        .filter(|(i, selector)| match adapter.selector_matches(selector) {
-            SelectorMatch::None => false,
-            SelectorMatch::Shared => true,
-            SelectorMatch::Exclusive => {
-                selectors.remove(*i);
-                true
-            }
-        })
-        .map(|(_, selector)| selector.clone())
-        .collect()
 }
 
 /// Collect all of the shell commands that should be run for the given selectors.
-pub fn collect_commands(selectors: &[TestSelector]) -> Vec<Cow<'_, str>> {
+pub fn collect_commands<'a>(
+    adapters: &'a [Adapter],
+    selectors: &[TestSelector],
+) -> Vec<Cow<'a, str>> {
     let mut selectors = selectors.to_vec();
 
-    let commands = adapter::all().iter().flat_map(|adapter| {
+    let commands = adapters.iter().flat_map(|adapter| {
         let matched = take_selectors(&mut selectors, adapter);
         let matched = matched.iter().collect::<Vec<&TestSelector>>();
 
@@ -49,8 +53,8 @@ pub fn collect_commands(selectors: &[TestSelector]) -> Vec<Cow<'_, str>> {
 }
 
 /// Run all the shell commands for the given selectors.
-pub fn run_all(selectors: &[TestSelector]) -> Result<(), Box<dyn Error>> {
-    for command in collect_commands(selectors) {
+pub fn run_all(adapters: &[Adapter], selectors: &[TestSelector]) -> Result<(), Box<dyn Error>> {
+    for command in collect_commands(adapters, selectors) {
         eprintln!("Running command: {}", command);
 
         let split_command = shell_words::split(&command)?;
@@ -72,7 +76,7 @@ pub fn run_all(selectors: &[TestSelector]) -> Result<(), Box<dyn Error>> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_env;
+    use crate::{adapter, config::Config, test_env};
 
     #[test]
     fn collect_commands_generates_correct_commands() {
@@ -85,7 +89,8 @@ mod tests {
                 },
                 TestSelector::NameOnly { name: "foo".into() },
             ];
-            let commands = collect_commands(&selectors);
+            let adapters = adapter::all(Config::default());
+            let commands = collect_commands(&adapters, &selectors);
 
             assert_eq!(commands.len(), 2);
             assert_eq!(commands[0], "bundle exec rspec --example foo");
@@ -102,10 +107,199 @@ mod tests {
                 path: "spec/foo_spec.rb".into(),
                 line: 1,
             }];
-            let commands = collect_commands(&selectors);
+            let adapters = adapter::all(Config::default());
+            let commands = collect_commands(&adapters, &selectors);
 
             assert_eq!(commands.len(), 1);
             assert_eq!(commands[0], "bundle exec rspec spec/foo_spec.rb:1");
         });
     }
+
+    #[test]
+    fn collect_commands_takes_every_exclusive_selector() {
+        test_env::with(|env| {
+            env.write_file("Gemfile", "gem 'rspec'");
+
+            let selectors = vec![
+                TestSelector::PathOnly {
+                    path: "spec/a_spec.rb".into(),
+                },
+                TestSelector::PathOnly {
+                    path: "spec/b_spec.rb".into(),
+                },
+                TestSelector::PathOnly {
+                    path: "spec/c_spec.rb".into(),
+                },
+            ];
+            let adapters = adapter::all(Config::default());
+            let commands = collect_commands(&adapters, &selectors);
+
+            assert_eq!(
+                commands,
+                vec!["bundle exec rspec spec/a_spec.rb spec/b_spec.rb spec/c_spec.rb"]
+            );
+        });
+    }
+
+    #[test]
+    fn collect_commands_uses_configured_file_patterns() {
+        test_env::with(|env| {
+            env.write_file("Gemfile", "gem 'rspec'");
+            env.write_file(
+                "ttest.toml",
+                "[ruby-rspec]\nfile-patterns = ['_check\\.rb$']",
+            );
+
+            let config = Config::load(&env.temp_dir).unwrap();
+            let selectors = vec![
+                TestSelector::PathOnly {
+                    path: "spec/foo_check.rb".into(),
+                },
+                TestSelector::PathOnly {
+                    path: "spec/foo_spec.rb".into(),
+                },
+            ];
+            let adapters = adapter::all(&config);
+            let commands = collect_commands(&adapters, &selectors);
+
+            assert_eq!(commands, vec!["bundle exec rspec spec/foo_check.rb"]);
+        });
+    }
+
+    #[test]
+    fn collect_commands_prefers_pytest_over_unittest() {
+        test_env::with(|env| {
+            env.write_file("tests/test_foo.py", "def test_foo():\n    pass\n");
+            let selectors = vec![TestSelector::PathWithLineNumber {
+                path: "tests/test_foo.py".into(),
+                line: 2,
+            }];
+            let adapters = adapter::all(Config::default());
+
+            assert_eq!(
+                collect_commands(&adapters, &selectors),
+                vec!["python -m unittest tests.test_foo"]
+            );
+
+            env.write_file("conftest.py", "");
+
+            assert_eq!(
+                collect_commands(&adapters, &selectors),
+                vec!["pytest tests/test_foo.py::test_foo"]
+            );
+        });
+    }
+
+    #[test]
+    fn collect_commands_batches_exclusive_selectors() {
+        test_env::with(|env| {
+            env.write_file("mix.exs", "");
+            env.write_file("test/foo_test.exs", "");
+            env.write_file("test/bar_test.exs", "");
+            env.write_file("test/baz_test.exs", "");
+            let selectors = vec![
+                TestSelector::PathWithLineNumber {
+                    path: "test/foo_test.exs".into(),
+                    line: 3,
+                },
+                TestSelector::PathOnly {
+                    path: "test/bar_test.exs".into(),
+                },
+                TestSelector::PathOnly {
+                    path: "test/baz_test.exs".into(),
+                },
+            ];
+            let adapters = adapter::all(Config::default());
+
+            assert_eq!(
+                collect_commands(&adapters, &selectors),
+                vec!["mix test test/foo_test.exs:3 test/bar_test.exs test/baz_test.exs"]
+            );
+        });
+    }
+
+    #[test]
+    fn collect_commands_prefers_vitest_over_jest() {
+        test_env::with(|env| {
+            env.write_file("src/add.test.ts", "it('adds', () => {\n});\n");
+            let selectors = vec![TestSelector::PathWithLineNumber {
+                path: "src/add.test.ts".into(),
+                line: 1,
+            }];
+            let adapters = adapter::all(Config::default());
+
+            assert_eq!(
+                collect_commands(&adapters, &selectors),
+                vec!["npx jest src/add.test.ts --testNamePattern '^adds$'"]
+            );
+
+            env.write_file("vitest.config.ts", "");
+
+            assert_eq!(
+                collect_commands(&adapters, &selectors),
+                vec!["npx vitest run src/add.test.ts -t '^adds$'"]
+            );
+        });
+    }
+
+    #[test]
+    fn collect_commands_prefers_bun_over_node() {
+        test_env::with(|env| {
+            env.write_file("package.json", r#"{"devDependencies": {"vitest": "*"}}"#);
+            env.write_file("src/add.test.ts", "it('adds', () => {\n});\n");
+            env.write_file("bun.lockb", "");
+            let selectors = vec![TestSelector::PathWithLineNumber {
+                path: "src/add.test.ts".into(),
+                line: 1,
+            }];
+            let adapters = adapter::all(Config::default());
+
+            assert_eq!(
+                collect_commands(&adapters, &selectors),
+                vec!["bun test src/add.test.ts -t '^adds$'"]
+            );
+        });
+    }
+
+    #[test]
+    fn collect_commands_never_runs_node_in_bun_projects() {
+        test_env::with(|env| {
+            env.write_file(
+                "package.json",
+                r#"{"devDependencies": {"jest": "*", "mocha": "*", "vitest": "*"}}"#,
+            );
+            env.write_file("src/__tests__/add.js", "it('adds', () => {\n});\n");
+            env.write_file("bun.lockb", "");
+            let selectors = vec![
+                TestSelector::NameOnly {
+                    name: "adds".into(),
+                },
+                TestSelector::PathOnly {
+                    path: "src/__tests__/add.js".into(),
+                },
+            ];
+            let adapters = adapter::all(Config::default());
+
+            assert_eq!(
+                collect_commands(&adapters, &selectors),
+                vec!["bun test -t adds", "bun test src/__tests__/add.js"]
+            );
+        });
+    }
+
+    #[test]
+    fn collect_commands_runs_ctest_names_once() {
+        test_env::with(|env| {
+            env.write_file("build/CTestTestfile.cmake", "");
+            let selectors = vec![TestSelector::NameOnly {
+                name: "Parses".into(),
+            }];
+            let adapters = adapter::all(Config::default());
+
+            assert_eq!(
+                collect_commands(&adapters, &selectors),
+                vec!["ctest --test-dir build -R Parses"]
+            );
+        });
+    }
 }
@@ -2,7 +2,7 @@ use lazy_static::lazy_static;
 use std::{env::temp_dir, panic::UnwindSafe, path::PathBuf, sync::Mutex};
 use uuid::Uuid;
 
-use crate::test_selector::TestSelector;
+use crate::{adapter::TestAdapter, test_selector::TestSelector};
 
 #[derive(Debug)]
 pub struct TestEnv {
@@ -36,6 +36,22 @@ impl TestEnv {
     pub fn selector(&self, path: &str) -> TestSelector {
         path.parse().unwrap()
     }
+
+    /// Collects the commands the adapter generates for the given selectors.
+    pub fn commands(&self, adapter: &impl TestAdapter, selectors: &[&str]) -> Vec<String> {
+        let selectors = selectors
+            .iter()
+            .map(|selector| self.selector(selector))
+            .collect::<Vec<_>>();
+        let selectors = selectors.iter().collect::<Vec<_>>();
+
+        adapter
+            .collect_commands(&selectors)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|command| command.to_string())
+            .collect()
+    }
 }
 
 impl Drop for TestEnv {
@@ -4,6 +4,8 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use regex::Regex;
+
 /// A wrapper around a [`PathBuf`] that has some additional functionality.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct TestFile(PathBuf);
@@ -14,12 +16,17 @@ impl TestFile {
         Self(path)
     }
 
-    /// Tests if the path matches a pattern.
-    pub fn matches(&self, pattern: &str) -> bool {
-        self.0
-            .to_str()
-            .map(|path| path.contains(pattern))
-            .unwrap_or(false)
+    /// Tests if the path matches any of the given regex patterns.
+    pub fn matches(&self, patterns: &[String]) -> bool {
+        let path = match self.0.to_str() {
+            Some(path) => path,
+            None => return false,
+        };
+
+        patterns
+            .iter()
+            .filter_map(|pattern| Regex::new(pattern).ok())
+            .any(|regex| regex.is_match(path))
     }
 
     /// Returns the path as a string.
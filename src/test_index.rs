@@ -1,8 +1,13 @@
-use std::{error::Error, fs::File, io::BufReader};
+use std::{collections::VecDeque, error::Error, fs::File, io::BufReader};
 
-use bstr::{io::BufReadExt, ByteSlice, Finder};
+use bstr::{io::BufReadExt, ByteSlice};
+use regex::{Regex, RegexBuilder};
 
-use crate::test_file::TestFile;
+use crate::{config::AdapterConfig, test_file::TestFile};
+
+/// The number of lines a pattern can span, so that attributes or annotations
+/// above a test can be required by its pattern.
+const WINDOW_SIZE: usize = 8;
 
 /// Searches and indexes a test file.
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -11,61 +16,539 @@ pub struct TestIndex {
 }
 
 impl TestIndex {
-    /// Creates a new [`TestIndex`] from a list of patterns.
-    pub fn build(test_file: &TestFile, patterns: &[&str]) -> Result<Self, Box<dyn Error>> {
-        let searchers = patterns.iter().map(Finder::new).collect::<Vec<_>>();
+    /// Creates a new [`TestIndex`] from the test and namespace patterns of an
+    /// adapter. If a pattern has a `name` capture group, the captured text is
+    /// recorded as the name of the entry.
+    ///
+    /// Patterns are matched in multi-line mode against the current line and
+    /// the few lines above it, and must end on the current line. This lets a
+    /// pattern like `#\[test\]\n\s*fn (?P<name>\w+)` index the `fn` line.
+    ///
+    /// Entries are nested by indentation. The body of an entry ends before the
+    /// next line indented no more than it is, and if that line closes the block
+    /// (`end`, `}`, ...) it is included in the body. An entry belongs to the
+    /// closest entry whose body encloses it, so tests can contain namespaces
+    /// or other tests (e.g. subtests).
+    pub fn build(test_file: &TestFile, config: &AdapterConfig) -> Result<Self, Box<dyn Error>> {
+        let test_searchers = compile(&config.test_patterns)?;
+        let namespace_searchers = compile(&config.namespace_patterns)?;
 
         let file = File::open(test_file)?;
         let mut reader = BufReader::new(file);
         let mut current_line = 0;
-        let mut entries = vec![];
+        let mut entries: Vec<IndexEntry> = vec![];
+        let mut lines: Vec<Option<LineShape>> = vec![];
+        let mut window: VecDeque<String> = VecDeque::with_capacity(WINDOW_SIZE);
 
         reader.for_byte_line_with_terminator(|line| {
             current_line += 1;
 
-            if searchers.iter().any(|s| s.find(line).is_some()) {
-                if let Ok(content) = line.to_str() {
-                    entries.push(IndexEntry::new(current_line, content.to_string()));
+            let content = match line.to_str() {
+                Ok(content) => content,
+                Err(_) => {
+                    lines.push(None);
+                    window.clear();
+                    return Ok(true);
                 }
+            };
+            lines.push(LineShape::of(content));
+
+            if window.len() == WINDOW_SIZE {
+                window.pop_front();
             }
+            window.push_back(content.to_string());
+
+            let text = window.iter().map(String::as_str).collect::<String>();
+            let line_start = text.len() - content.len();
+
+            let (kind, captures) =
+                if let Some(c) = find_captures(&test_searchers, &text, line_start) {
+                    (EntryKind::Test, c)
+                } else if let Some(c) = find_captures(&namespace_searchers, &text, line_start) {
+                    (EntryKind::Namespace, c)
+                } else {
+                    return Ok(true);
+                };
+
+            let name = captures.name("name").map(|m| m.as_str().to_string());
+            let mut entry = IndexEntry::new(current_line, content.to_string(), name);
+            entry.kind = kind;
+            entry.indent = indentation(content);
+            entries.push(entry);
 
             Ok(true)
         })?;
 
+        let mut open: Vec<usize> = vec![];
+
+        for i in 0..entries.len() {
+            entries[i].end_line_number = end_line_number(&lines, &entries[i]);
+
+            while let Some(&j) = open.last() {
+                if entries[j].indent < entries[i].indent
+                    && entries[j].encloses(entries[i].line_number)
+                {
+                    break;
+                }
+                open.pop();
+            }
+
+            entries[i].parent = open.last().copied();
+            open.push(i);
+        }
+
         Ok(Self { entries })
     }
 
-    /// Returns the closest [`IndexEntry`] to the given line number.
-    pub fn closest_to_line_number(&self, line: u32) -> Option<IndexEntry> {
+    /// Returns the innermost entry whose body encloses the given line. If this
+    /// is a namespace, the line is between tests; if there is no such entry,
+    /// the line is outside of any test or namespace.
+    pub fn enclosing(&self, line: u32) -> Option<&IndexEntry> {
         self.entries
             .iter()
-            .min_by(|a, b| {
-                let a_distance = a.line_number.abs_diff(line);
-                let b_distance = b.line_number.abs_diff(line);
-                b_distance.cmp(&a_distance)
-            })
-            .cloned()
+            .filter(|entry| entry.encloses(line))
+            .max_by_key(|entry| entry.line_number)
     }
+
+    /// Returns the entries the given entry is nested in, outermost first.
+    pub fn ancestors(&self, entry: &IndexEntry) -> Vec<&IndexEntry> {
+        let mut ancestors = vec![];
+        let mut parent = entry.parent;
+
+        while let Some(i) = parent {
+            ancestors.push(&self.entries[i]);
+            parent = self.entries[i].parent;
+        }
+
+        ancestors.reverse();
+        ancestors
+    }
+
+    /// Returns the entries which are not nested in any other entry.
+    pub fn top_level(&self) -> Vec<&IndexEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.parent.is_none())
+            .collect()
+    }
+
+    /// Returns the test entries, in the order they appear in the file.
+    pub fn tests(&self) -> Vec<&IndexEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.is_test())
+            .collect()
+    }
+}
+
+/// The kind of an [`IndexEntry`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum EntryKind {
+    /// A test case, matched by one of the test patterns.
+    Test,
+    /// A group of tests, matched by one of the namespace patterns.
+    Namespace,
 }
 
-/// An indexed test.
+/// An indexed test or namespace.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct IndexEntry {
     line_number: u32,
+    end_line_number: u32,
     content: String,
+    name: Option<String>,
+    kind: EntryKind,
+    indent: usize,
+    parent: Option<usize>,
 }
 
 impl IndexEntry {
-    /// Creates a new [`IndexEntry`] from a line number and content.
-    pub fn new(line_number: u32, content: String) -> Self {
+    /// Creates a new top-level test [`IndexEntry`] from a line number, content
+    /// and the captured name.
+    pub fn new(line_number: u32, content: String, name: Option<String>) -> Self {
         Self {
             line_number,
+            end_line_number: line_number,
             content,
+            name,
+            kind: EntryKind::Test,
+            indent: 0,
+            parent: None,
         }
     }
 
+    /// Returns the line number the entry starts on.
+    pub fn line_number(&self) -> u32 {
+        self.line_number
+    }
+
     /// Returns the content of the test.
     pub fn content(&self) -> &str {
         &self.content
     }
+
+    /// Returns the name captured by the `name` group of the matching pattern.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns true if the entry is a test, rather than a namespace.
+    pub fn is_test(&self) -> bool {
+        self.kind == EntryKind::Test
+    }
+
+    /// Returns true if the given line is between the first and last line of
+    /// the entry.
+    pub fn encloses(&self, line: u32) -> bool {
+        (self.line_number..=self.end_line_number).contains(&line)
+    }
+}
+
+/// The indentation of a non-blank line, and whether it opens or closes a
+/// block.
+#[derive(Debug, Clone, Copy)]
+struct LineShape {
+    indent: usize,
+    is_opener: bool,
+    is_closer: bool,
+}
+
+impl LineShape {
+    fn of(line: &str) -> Option<Self> {
+        let trimmed = line.trim_start();
+
+        if trimmed.trim_end().is_empty() {
+            return None;
+        }
+
+        let is_closer = (trimmed.starts_with("end")
+            && !trimmed[3..].starts_with(|c: char| c.is_alphanumeric() || c == '_'))
+            || trimmed.starts_with(['}', ')', ']']);
+
+        Some(Self {
+            indent: indentation(line),
+            is_opener: trimmed.starts_with('{'),
+            is_closer,
+        })
+    }
+}
+
+/// Finds the last line of the body of the entry, given the shapes of all of
+/// the lines in the file.
+fn end_line_number(lines: &[Option<LineShape>], entry: &IndexEntry) -> u32 {
+    let mut end = entry.line_number;
+
+    for (i, shape) in lines.iter().enumerate().skip(entry.line_number as usize) {
+        let line_number = i as u32 + 1;
+
+        match shape {
+            // A brace on its own line right after the entry opens its body.
+            Some(shape) if shape.is_opener && end == entry.line_number => end = line_number,
+            Some(shape) if shape.indent <= entry.indent => {
+                if shape.is_closer && shape.indent == entry.indent {
+                    end = line_number;
+                }
+                break;
+            }
+            Some(_) => end = line_number,
+            None => {}
+        }
+    }
+
+    end
+}
+
+fn compile(patterns: &[String]) -> Result<Vec<Regex>, regex::Error> {
+    patterns
+        .iter()
+        .map(|pattern| RegexBuilder::new(pattern).multi_line(true).build())
+        .collect()
+}
+
+/// Finds the first match which ends on the last line of the text, which
+/// starts at `line_start`.
+fn find_captures<'a>(
+    searchers: &[Regex],
+    text: &'a str,
+    line_start: usize,
+) -> Option<regex::Captures<'a>> {
+    searchers.iter().find_map(|s| {
+        s.captures_iter(text)
+            .find(|c| matches!(c.get(0), Some(m) if m.end() > line_start))
+    })
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_env;
+
+    fn config(test_patterns: &[&str], namespace_patterns: &[&str]) -> AdapterConfig {
+        AdapterConfig {
+            test_patterns: test_patterns.iter().map(|p| p.to_string()).collect(),
+            namespace_patterns: namespace_patterns.iter().map(|p| p.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn names<'a>(entries: &[&'a IndexEntry]) -> Vec<&'a str> {
+        entries.iter().filter_map(|entry| entry.name()).collect()
+    }
+
+    #[test]
+    fn build_records_captured_names() {
+        test_env::with(|env| {
+            env.write_file(
+                "my_test.rb",
+                "def test_foo\nend\n\n    test(\"does bar\") do\n  end\n",
+            );
+
+            let config = config(
+                &[
+                    r#"^\s*test(\(| )"(?P<name>.*)""#,
+                    r"^\s*def (?P<name>test_\w+)",
+                ],
+                &[],
+            );
+            let index = TestIndex::build(&"my_test.rb".into(), &config).unwrap();
+
+            assert_eq!(
+                index.entries,
+                vec![
+                    IndexEntry {
+                        end_line_number: 2,
+                        ..IndexEntry::new(1, "def test_foo\n".into(), Some("test_foo".into()))
+                    },
+                    IndexEntry {
+                        indent: 4,
+                        ..IndexEntry::new(
+                            4,
+                            "    test(\"does bar\") do\n".into(),
+                            Some("does bar".into())
+                        )
+                    },
+                ]
+            );
+        })
+    }
+
+    #[test]
+    fn build_without_name_group_records_no_name() {
+        test_env::with(|env| {
+            env.write_file("my_test.rb", "def test_foo\nend\n");
+
+            let config = config(&["def test_"], &[]);
+            let index = TestIndex::build(&"my_test.rb".into(), &config).unwrap();
+
+            assert_eq!(
+                index.entries,
+                vec![IndexEntry {
+                    end_line_number: 2,
+                    ..IndexEntry::new(1, "def test_foo\n".into(), None)
+                }]
+            );
+        })
+    }
+
+    #[test]
+    fn build_rejects_invalid_patterns() {
+        test_env::with(|env| {
+            env.write_file("my_test.rb", "");
+
+            let result = TestIndex::build(&"my_test.rb".into(), &config(&["(unclosed"], &[]));
+
+            assert!(result.is_err());
+        })
+    }
+
+    #[test]
+    fn build_nests_entries_by_indentation() {
+        test_env::with(|env| {
+            let content = r#"
+module Foo
+  describe "bar" do
+    context "when baz" do
+      it "does qux" do
+      end
+    end
+
+    it "does quux" do
+    end
+  end
+end
+"#;
+            env.write_file("my_spec.rb", content);
+
+            let config = config(
+                &[r#"^\s*it "(?P<name>.*)""#],
+                &[
+                    r"^\s*module (?P<name>\S+)",
+                    r#"^\s*(describe|context) "(?P<name>.*)""#,
+                ],
+            );
+            let index = TestIndex::build(&"my_spec.rb".into(), &config).unwrap();
+
+            let tests = index
+                .entries
+                .iter()
+                .filter(|entry| entry.is_test())
+                .map(|test| names(&index.ancestors(test)))
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                tests,
+                vec![vec!["Foo", "bar", "when baz"], vec!["Foo", "bar"]]
+            );
+            assert_eq!(index.entries[0].kind, EntryKind::Namespace);
+            assert!(index.ancestors(&index.entries[0]).is_empty());
+        })
+    }
+
+    #[test]
+    fn ancestors_are_outermost_first() {
+        test_env::with(|env| {
+            env.write_file(
+                "my_test.rb",
+                "module Foo\n  class BarTest\n    def test_baz\n    end\n  end\nend\n",
+            );
+
+            let config = config(
+                &[r"^\s*def (?P<name>test_\w+)"],
+                &[r"^\s*(class|module) (?P<name>\S+)"],
+            );
+            let index = TestIndex::build(&"my_test.rb".into(), &config).unwrap();
+            let test = index.enclosing(3).unwrap();
+
+            assert!(test.is_test());
+            assert_eq!(names(&index.ancestors(test)), vec!["Foo", "BarTest"]);
+        })
+    }
+
+    fn spec_index(env: &test_env::TestEnv) -> TestIndex {
+        let content = r#"require "helper"
+
+describe "foo" do
+  it "does bar" do
+    assert true
+  end
+
+  it "does baz" do
+    assert true
+  end
+end
+
+# trailing comment
+"#;
+        env.write_file("my_spec.rb", content);
+
+        let config = config(
+            &[r#"^\s*it "(?P<name>.*)""#],
+            &[r#"^\s*describe "(?P<name>.*)""#],
+        );
+
+        TestIndex::build(&"my_spec.rb".into(), &config).unwrap()
+    }
+
+    #[test]
+    fn enclosing_finds_the_test_around_a_line() {
+        test_env::with(|env| {
+            let index = spec_index(env);
+
+            for line in 4..=6 {
+                assert_eq!(index.enclosing(line).unwrap().name(), Some("does bar"));
+            }
+            for line in 8..=10 {
+                assert_eq!(index.enclosing(line).unwrap().name(), Some("does baz"));
+            }
+        })
+    }
+
+    #[test]
+    fn enclosing_falls_back_to_the_namespace_between_tests() {
+        test_env::with(|env| {
+            let index = spec_index(env);
+
+            for line in [3, 7, 11] {
+                let enclosing = index.enclosing(line).unwrap();
+
+                assert!(!enclosing.is_test());
+                assert_eq!(enclosing.name(), Some("foo"));
+            }
+        })
+    }
+
+    #[test]
+    fn enclosing_is_none_before_and_after_tests() {
+        test_env::with(|env| {
+            let index = spec_index(env);
+
+            for line in [1, 2, 12, 13, 100] {
+                assert_eq!(index.enclosing(line), None);
+            }
+        })
+    }
+
+    #[test]
+    fn enclosing_uses_indentation_for_blocks_without_closers() {
+        test_env::with(|env| {
+            let content = "class TestFoo:\n    def test_bar(self):\n        pass\n\n    def test_baz(self):\n        pass\n";
+            env.write_file("test_foo.py", content);
+
+            let config = config(
+                &[r"^\s*def (?P<name>test_\w+)"],
+                &[r"^\s*class (?P<name>\w+)"],
+            );
+            let index = TestIndex::build(&"test_foo.py".into(), &config).unwrap();
+
+            assert_eq!(index.enclosing(3).unwrap().name(), Some("test_bar"));
+            assert_eq!(index.enclosing(4).unwrap().name(), Some("TestFoo"));
+            assert_eq!(index.enclosing(6).unwrap().name(), Some("test_baz"));
+        })
+    }
+
+    #[test]
+    fn build_nests_tests_within_tests() {
+        test_env::with(|env| {
+            let content = "func TestFoo(t *testing.T) {\n\tt.Run(\"bar\", func(t *testing.T) {\n\t\tt.Fail()\n\t})\n}\n";
+            env.write_file("foo_test.go", content);
+
+            let config = config(
+                &[
+                    r"^func (?P<name>Test\w*)",
+                    r#"^\s*t\.Run\("(?P<name>[^"]*)""#,
+                ],
+                &[],
+            );
+            let index = TestIndex::build(&"foo_test.go".into(), &config).unwrap();
+            let subtest = index.enclosing(3).unwrap();
+
+            assert_eq!(subtest.name(), Some("bar"));
+            assert_eq!(names(&index.ancestors(subtest)), vec!["TestFoo"]);
+            assert_eq!(names(&index.top_level()), vec!["TestFoo"]);
+            assert_eq!(index.enclosing(5).unwrap().name(), Some("TestFoo"));
+        })
+    }
+
+    #[test]
+    fn build_includes_braces_on_their_own_line() {
+        test_env::with(|env| {
+            let content = "class FooTest\n{\n    public function testBar()\n    {\n        $this->fail();\n    }\n}\n";
+            env.write_file("FooTest.php", content);
+
+            let config = config(
+                &[r"^\s*public function (?P<name>test\w*)"],
+                &[r"^class (?P<name>\w+)"],
+            );
+            let index = TestIndex::build(&"FooTest.php".into(), &config).unwrap();
+            let test = index.enclosing(5).unwrap();
+
+            assert_eq!(test.name(), Some("testBar"));
+            assert_eq!(names(&index.ancestors(test)), vec!["FooTest"]);
+            assert_eq!(index.enclosing(7).unwrap().name(), Some("FooTest"));
+        })
+    }
 }
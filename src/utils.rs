@@ -2,7 +2,7 @@ use std::{
     env::current_dir,
     fs::File,
     io::{BufRead, BufReader},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
 /// Test if the pattern is in a Gemfile
@@ -27,19 +27,24 @@ pub fn file_exists<T: Into<PathBuf>>(path: T) -> bool {
         .unwrap_or(false)
 }
 
+/// Find the closest file with the given name, looking in the given directory
+/// and then in each of its parents. Relative paths are resolved against the
+/// current directory.
+pub fn find_up<T: AsRef<Path>>(dir: T, name: &str) -> Option<PathBuf> {
+    let dir = current_dir().ok()?.join(dir);
+
+    dir.ancestors()
+        .map(|ancestor| ancestor.join(name))
+        .find(|path| path.is_file())
+}
+
 #[macro_export]
 #[cfg(not(test))]
 macro_rules! memoize_string {
     ($val:expr) => {{
-        static INIT: std::sync::Once = std::sync::Once::new();
-        static mut VALUE: Option<String> = None;
-        unsafe {
-            INIT.call_once(|| {
-                let s: String = $val.into();
-                VALUE = Some(s);
-            });
-            VALUE.as_ref().unwrap().into()
-        }
+        static VALUE: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+
+        VALUE.get_or_init(|| $val.into()).as_str().into()
     }};
 }
 
@@ -64,25 +69,37 @@ macro_rules! none_if_empty {
 
 #[macro_export]
 macro_rules! define_adapters {
-    ($($key:expr => $value:ident),*) => {
-        impl TryFrom<&str> for $crate::adapter::Adapter {
-            type Error = Box<dyn std::error::Error>;
-            /// Fetch an adapter for the given name
-            fn try_from(name: &str) -> Result<Self, Self::Error> {
+    ($($key:ident => $value:ident),*) => {
+        impl $crate::adapter::Adapter {
+            /// Fetch an adapter for the given name, configured from `config`.
+            pub fn from_config(
+                name: &str,
+                config: &$crate::config::Config,
+            ) -> Result<Self, Box<dyn std::error::Error>> {
                 match name {
-                    $($key => Ok($crate::adapter::Adapter::$value($value {})),)*
+                    $(stringify!($key) => Ok($crate::adapter::Adapter::$value(
+                        $value::new(config.$key.clone())
+                    )),)*
                     _ => Err("Unknown adapter".into()),
                 }
             }
         }
 
+        impl TryFrom<&str> for $crate::adapter::Adapter {
+            type Error = Box<dyn std::error::Error>;
+            /// Fetch an adapter for the given name, using the default config.
+            fn try_from(name: &str) -> Result<Self, Self::Error> {
+                Self::from_config(name, $crate::config::Config::default())
+            }
+        }
+
         #[enum_dispatch]
         #[derive(Debug, PartialEq, Eq)]
         pub enum Adapter { $($value($value),)* }
 
-        /// Get a list of all adapters.
-        pub const fn all() -> &'static [$crate::adapter::Adapter] {
-            &[$($crate::adapter::Adapter::$value($value {})),*]
+        /// Get a list of all adapters, configured from `config`.
+        pub fn all(config: &$crate::config::Config) -> Vec<$crate::adapter::Adapter> {
+            vec![$($crate::adapter::Adapter::$value($value::new(config.$key.clone()))),*]
         }
     };
 }